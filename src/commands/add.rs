@@ -3,21 +3,22 @@ use std::{
     io::Write,
 };
 
-use anyhow::bail;
+use anyhow::{bail, Context};
 use clap::Parser;
 use inflector::cases::titlecase::to_title_case;
-use slog::info;
+use slog::{info, warn, Logger};
 
 use crate::{
     lib::{
         codegen::{
+            chains::ChainRegistry,
             components::{
                 algorithm_indexer::{
                     AlgorithmIndexerComponentManifest, AlgorithmIndexerDatasource,
                     AlgorithmIndexerOutput,
                 },
                 algorithm_lens::{AlgorithmLensComponentManifest, AlgorithmLensDataSource},
-                common::{ComponentManifest, DatasourceForCanister},
+                common::{ComponentManifest, DatasourceForCanister, DestinationType},
                 event_indexer::{
                     EventIndexerComponentManifest, EventIndexerDatasource,
                     EventIndexerEventDefinition, SourceNetwork,
@@ -59,6 +60,11 @@ pub struct AddOpts {
     /// If not specified, the current directory is targeted.
     #[arg(long, short = 'p')]
     path: Option<String>,
+
+    /// Specifies the chain id of the destination of a relayer, required to add a relayer.
+    /// The oracle address and rpc url are taken from the chain registry.
+    #[arg(long)]
+    chain_id: Option<u32>,
 }
 
 pub fn exec<U: UserInteraction>(
@@ -103,7 +109,14 @@ pub fn exec<U: UserInteraction>(
         ComponentType::SnapshotIndexerEVM => {
             template_snapshot_indexer_evm_manifest(&component_name).to_str_as_yaml()
         }
-        ComponentType::Relayer => template_relayer_manifest(&component_name).to_str_as_yaml(),
+        ComponentType::Relayer => {
+            let destination = template_relayer_destination(
+                log,
+                project_path.as_deref().unwrap_or("."),
+                opts.chain_id,
+            )?;
+            template_relayer_manifest(&component_name, destination).to_str_as_yaml()
+        }
         ComponentType::AlgorithmLens => {
             template_algorithm_lens_manifest(&component_name).to_str_as_yaml()
         }
@@ -221,18 +234,53 @@ fn template_snapshot_indexer_evm_manifest(
     )
 }
 
-fn template_relayer_manifest(component_name: &str) -> RelayerComponentManifest {
+fn template_relayer_manifest(
+    component_name: &str,
+    destination: DestinationField,
+) -> RelayerComponentManifest {
     RelayerComponentManifest::new(
         component_name,
         &to_title_case(component_name),
         "",
         PROJECT_MANIFEST_VERSION,
         DatasourceForCanister::default(),
-        DestinationField::default(),
+        destination,
         3600,
     )
 }
 
+fn template_relayer_destination(
+    log: &Logger,
+    project_root: &str,
+    chain_id: Option<u32>,
+) -> anyhow::Result<DestinationField> {
+    let chain_id = chain_id.context("--chain-id is required to add a relayer")?;
+    let registry = ChainRegistry::load(project_root)?;
+    let destination =
+        DestinationField::from_registry(&registry, chain_id, DestinationType::Uint256);
+    match registry.get(chain_id) {
+        Some(chain) => {
+            if chain.deprecated {
+                warn!(log, "Chain {} ({}) is deprecated", chain_id, chain.name);
+            }
+            if destination.oracle_address.is_empty() {
+                warn!(
+                    log,
+                    "No oracle on chain {} ({}) in the chain registry, fill in destination.oracle_address",
+                    chain_id,
+                    chain.name
+                );
+            }
+        }
+        None => warn!(
+            log,
+            "Chain {} is not in the chain registry, fill in destination.oracle_address and destination.rpc_url",
+            chain_id
+        ),
+    }
+    Ok(destination)
+}
+
 fn template_algorithm_lens_manifest(component_name: &str) -> AlgorithmLensComponentManifest {
     AlgorithmLensComponentManifest::new(
         component_name,
@@ -339,6 +387,11 @@ mod tests {
     }
 
     const COMPONENT_NAME: &str = "additional";
+    const CHAIN_ID: u32 = 11155111;
+
+    fn relayer_chain_id(component: &ComponentType) -> Option<u32> {
+        (*component == ComponentType::Relayer).then_some(CHAIN_ID)
+    }
 
     #[test]
     fn test_add() {
@@ -352,6 +405,7 @@ mod tests {
                         component_name: Some(format!("test_{}", name)),
                         type_: Some(*component),
                         path: Some(project_name.to_string()),
+                        chain_id: relayer_chain_id(component),
                     };
                     let mut interaction = MockUserInteraction::default();
                     exec(&test_env(), opts, &mut interaction).unwrap();
@@ -369,6 +423,31 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_add_relayer_without_chain_id() {
+        let project_name = "create_test__add_relayer_without_chain_id";
+        run(
+            || setup(project_name),
+            || {
+                let opts = AddOpts {
+                    component_name: Some("test_relayer".to_string()),
+                    type_: Some(ComponentType::Relayer),
+                    path: Some(project_name.to_string()),
+                    chain_id: None,
+                };
+                let mut interaction = MockUserInteraction::default();
+                let err = exec(&test_env(), opts, &mut interaction).unwrap_err();
+                assert_eq!(err.to_string(), "--chain-id is required to add a relayer");
+                assert!(
+                    !Path::new(&format!("{}/components/test_relayer.yaml", project_name)).exists()
+                );
+            },
+            || {
+                teardown(project_name);
+            },
+        )
+    }
+
     #[test]
     fn test_add_without_args() {
         let projects = added_projects();
@@ -382,6 +461,7 @@ mod tests {
                         component_name: None,
                         type_: None,
                         path: Some(project_name.to_string()),
+                        chain_id: relayer_chain_id(component),
                     };
                     let component_idx = all_component_types
                         .iter()
@@ -510,15 +590,29 @@ mod tests {
     #[test]
     fn test_manifest_validate_with_schema_relayer() {
         validate_manifest_with_schema(
-            template_relayer_manifest(COMPONENT_NAME),
+            template_relayer_manifest(
+                COMPONENT_NAME,
+                DestinationField::from_registry(
+                    &ChainRegistry::bundled(),
+                    CHAIN_ID,
+                    DestinationType::Uint256,
+                ),
+            ),
             include_str!("../../resources/schema/relayer.json"),
         );
     }
 
     #[test]
     fn test_manifest_snapshot_relayer() {
-        assert_display_snapshot!(template_relayer_manifest(COMPONENT_NAME)
-            .to_str_as_yaml()
-            .unwrap());
+        assert_display_snapshot!(template_relayer_manifest(
+            COMPONENT_NAME,
+            DestinationField::from_registry(
+                &ChainRegistry::bundled(),
+                CHAIN_ID,
+                DestinationType::Uint256
+            )
+        )
+        .to_str_as_yaml()
+        .unwrap());
     }
 }
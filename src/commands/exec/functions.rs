@@ -1,4 +1,6 @@
+use anyhow::Context;
 use candid::{Encode, Principal};
+use chainsight_cdk::initializer::CycleManagements;
 use ic_utils::{interfaces::WalletCanister, Argument};
 
 use crate::{lib::codegen::components::common::TimerSettings, types::Network};
@@ -8,51 +10,46 @@ pub async fn call_init_in(
     target: Principal,
     network: &Network,
     subnet: &Option<Principal>,
+    cycles_managements: &CycleManagements,
 ) -> anyhow::Result<()> {
-    // todo: make customisable
-    let (cycles_managements, total_initial_supply) = default_cycle_managements();
     let cdk_env = match network {
         Network::Local => chainsight_cdk::core::Env::LocalDevelopment,
         Network::IC => chainsight_cdk::core::Env::Production,
     };
-    let raw_args = Encode!(&cdk_env, &cycles_managements, subnet)?;
+    let raw_args = Encode!(&cdk_env, cycles_managements, subnet)?;
     wallet_call128(
         wallet,
         target,
         "init_in".to_string(),
         raw_args,
-        Some(total_initial_supply),
+        Some(total_initial_supply(cycles_managements)?),
     )
     .await?;
 
     Ok(())
 }
 
-fn default_cycle_managements() -> (chainsight_cdk::initializer::CycleManagements, u128) {
-    let datum = chainsight_cdk::initializer::CycleManagements {
-        refueling_interval: 86400,
-        vault_intial_supply: 500_000_000_000u128,
-        indexer: chainsight_cdk::initializer::CycleManagement {
-            initial_supply: 0u128,
-            refueling_amount: 3_000_000_000_000u128,
-            refueling_threshold: 1_500_000_000_000u128,
-        },
-        db: chainsight_cdk::initializer::CycleManagement {
-            initial_supply: 0u128,
-            refueling_amount: 0u128,
-            refueling_threshold: 0u128,
-        },
-        proxy: chainsight_cdk::initializer::CycleManagement {
-            initial_supply: 500_000_000_000u128,
-            refueling_amount: 500_000_000_000u128,
-            refueling_threshold: 500_000_000_000u128,
-        },
-    };
-    let total_initial_supply = datum.vault_intial_supply
-        + datum.indexer.initial_supply
-        + datum.db.initial_supply
-        + datum.proxy.initial_supply;
-    (datum, total_initial_supply)
+/// Cycles attached to init_in, supplied to the vault, indexer, db and proxy initially
+pub fn total_initial_supply(cycles_managements: &CycleManagements) -> anyhow::Result<u128> {
+    [
+        cycles_managements.indexer.initial_supply,
+        cycles_managements.db.initial_supply,
+        cycles_managements.proxy.initial_supply,
+    ]
+    .into_iter()
+    .try_fold(cycles_managements.vault_intial_supply, |total, supply| {
+        total.checked_add(supply)
+    })
+    .context("Total initial supply of cycles overflows")
+}
+
+/// Format cycles with the amount in trillion cycles (TC)
+pub fn format_cycles(cycles: u128) -> String {
+    format!(
+        "{} ({:.3} TC)",
+        cycles,
+        cycles as f64 / 1_000_000_000_000f64
+    )
 }
 
 pub async fn call_setup(
@@ -95,3 +92,48 @@ async fn wallet_call128(
         .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::codegen::components::common::{
+        CycleManagementManifest, CycleManagementsManifest,
+    };
+
+    #[test]
+    fn test_total_initial_supply() {
+        let defaults: CycleManagements = CycleManagementsManifest::default().into();
+        assert_eq!(total_initial_supply(&defaults).unwrap(), 2_000_000_000_000);
+
+        let configured: CycleManagements = CycleManagementsManifest {
+            vault_intial_supply: Some(1_000_000_000_000),
+            indexer: Some(CycleManagementManifest {
+                initial_supply: Some(2_000_000_000_000),
+                ..Default::default()
+            }),
+            db: Some(CycleManagementManifest {
+                initial_supply: Some(0),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+        .into();
+        assert_eq!(
+            total_initial_supply(&configured).unwrap(),
+            3_500_000_000_000
+        );
+
+        let overflowed: CycleManagements = CycleManagementsManifest {
+            vault_intial_supply: Some(u128::MAX),
+            ..Default::default()
+        }
+        .into();
+        assert!(total_initial_supply(&overflowed).is_err());
+    }
+
+    #[test]
+    fn test_format_cycles() {
+        assert_eq!(format_cycles(2_500_000_000_000), "2500000000000 (2.500 TC)");
+        assert_eq!(format_cycles(0), "0 (0.000 TC)");
+    }
+}
@@ -3,9 +3,13 @@ use std::{collections::BTreeMap, path::Path};
 use anyhow::{bail, Context};
 use candid::{types::principal, Principal};
 use clap::{arg, Parser};
-use functions::{call_init_in, call_set_task, call_setup};
+use functions::{call_init_in, call_set_task, call_setup, format_cycles, total_initial_supply};
 use ic_wasm::info;
 use slog::{info, warn, Logger};
+use state::{
+    format_summary, query_init_state, query_setup_state, query_task_state, ComponentSummary, Phase,
+    PhaseState, PhaseStatus,
+};
 
 use crate::{
     commands::utils::get_agent,
@@ -18,6 +22,7 @@ use crate::{
         ic_api::get_canister_with_retry,
         utils::{
             component_ids_manager::ComponentIdsManager,
+            cron::{format_utc, now_sec, PREVIEW_COUNT},
             dfx::DfxWrapperNetwork,
             env::cache_envfile,
             identity::{
@@ -30,6 +35,7 @@ use crate::{
 };
 
 mod functions;
+mod state;
 
 const ALREADY_INIT_IN_PANIC_MSG: &str = "Already initialized";
 const ALREADY_SETUP_PANIC_MSG: &str = "Already setup";
@@ -73,6 +79,11 @@ pub struct ExecOpts {
     #[arg(long)]
     port: Option<u16>,
 
+    /// Execute only the specified phase: init (init_in), setup or task (set_task).
+    /// If this option is not specified, all phases not done yet are executed.
+    #[arg(long, value_enum)]
+    only: Option<Phase>,
+
     /// Force execution even if the component has already been executed.
     /// If this option is specified, the process continues without panic if it has already been executed at runtime.
     #[arg(long)]
@@ -107,6 +118,7 @@ pub async fn exec(env: &EnvironmentImpl, opts: ExecOpts) -> anyhow::Result<()> {
         opts.network,
         opts.subnet,
         opts.port,
+        opts.only,
         opts.force,
     )
     .await?;
@@ -133,6 +145,7 @@ async fn execute_initialize_components(
     network: Network,
     subnet: Option<String>,
     port: Option<u16>,
+    only: Option<Phase>,
     force: bool,
 ) -> anyhow::Result<()> {
     // loading component ids
@@ -160,8 +173,127 @@ async fn execute_initialize_components(
     };
     let wallet = wallet_canister(wallet_canister_id, &agent).await?;
 
-    // exec: init_in
+    let project_manifest = ProjectManifestData::load(&format!(
+        "{}/{}",
+        &project_path_str, PROJECT_MANIFEST_FILENAME
+    ))?;
+    let component_path_mapping: BTreeMap<String, (ComponentType, String)> = project_manifest
+        .components
+        .iter()
+        .map(|c| {
+            let c_path = format!("{}/{}", &project_path_str, c.component_path);
+            let c_type = ComponentTypeInManifest::determine_type(&c_path)
+                .unwrap_or_else(|_| panic!("Failed to determine component type: {}", &c_path));
+            let id = Path::new(&c_path).file_stem().unwrap().to_str().unwrap();
+            (id.to_owned(), (c_type, c_path))
+        })
+        .collect();
+
+    // query the state of each phase, to execute only the missing ones
+    let mut states = vec![];
     for (name, comp_id) in &components {
+        let (component_type, component_path) = component_path_mapping
+            .get(name.as_str())
+            .context(format!("Component not found: {}", &name))?;
+        let generator =
+            codegen::generator(*component_type, project_path_str, component_path, name)?;
+        let canister = Principal::from_text(comp_id)?;
+        let init = query_init_state(&agent, &canister).await;
+        if let PhaseState::Unknown(reason) = &init {
+            info!(
+                log,
+                "Failed to query the state of init_in: {} ({}): {}", name, comp_id, reason
+            );
+        }
+        let setup = match generator.generate_component_setup_args(&network, &comp_id_mgr)? {
+            Some(_) => query_setup_state(&agent, &canister).await,
+            None => PhaseState::NotApplicable,
+        };
+        let (task, task_interval_sec) = match generator.manifest().timer_settings() {
+            Some(settings) => {
+                let (task, interval_sec) = query_task_state(&agent, &canister).await;
+                if let Some(interval_sec) = interval_sec.filter(|i| *i != settings.interval_sec) {
+                    warn!(
+                        log,
+                        "Task runs every {}s, not every {}s in the manifest: {} ({})",
+                        interval_sec,
+                        settings.interval_sec,
+                        name,
+                        comp_id
+                    );
+                }
+                (task, interval_sec)
+            }
+            None => (PhaseState::NotApplicable, None),
+        };
+        states.push((init, setup, task, task_interval_sec));
+    }
+    let mut summaries = components
+        .iter()
+        .zip(&states)
+        .map(
+            |((name, _), (init, setup, task, task_interval_sec))| ComponentSummary {
+                name: name.clone(),
+                init: PhaseStatus::from_state(init),
+                setup: PhaseStatus::from_state(setup),
+                task: PhaseStatus::from_state(task),
+                task_interval_sec: *task_interval_sec,
+            },
+        )
+        .collect::<Vec<_>>();
+    let to_execute = |phase: Phase, state: &PhaseState| {
+        only.map_or(true, |only| only == phase)
+            && matches!(state, PhaseState::Pending | PhaseState::Unknown(_))
+    };
+
+    // cycles to be spent by init_in, configured in each manifest
+    let mut cycles_managements = BTreeMap::new();
+    for ((name, comp_id), (init, ..)) in components.iter().zip(&states) {
+        if !to_execute(Phase::Init, init) {
+            continue;
+        }
+        let (component_type, component_path) = component_path_mapping
+            .get(name.as_str())
+            .context(format!("Component not found: {}", &name))?;
+        let generator =
+            codegen::generator(*component_type, project_path_str, component_path, name)?;
+        let cycles = generator.manifest().cycle_managements();
+        info!(
+            log,
+            "Cycles to spend on init_in: {} ({}): {} = vault {} + indexer {} + db {} + proxy {}",
+            name,
+            comp_id,
+            format_cycles(
+                total_initial_supply(&cycles)
+                    .with_context(|| format!("Invalid cycles in the manifest of {}", name))?
+            ),
+            cycles.vault_intial_supply,
+            cycles.indexer.initial_supply,
+            cycles.db.initial_supply,
+            cycles.proxy.initial_supply
+        );
+        cycles_managements.insert(name.clone(), cycles);
+    }
+    if !cycles_managements.is_empty() {
+        let total = cycles_managements
+            .values()
+            .try_fold(0u128, |total, cycles| {
+                total
+                    .checked_add(total_initial_supply(cycles)?)
+                    .context("Total cycles to spend on init_in overflows")
+            })?;
+        info!(
+            log,
+            "Total cycles to spend on init_in: {}",
+            format_cycles(total)
+        );
+    }
+
+    // exec: init_in
+    for (i, (name, comp_id)) in components.iter().enumerate() {
+        if !to_execute(Phase::Init, &states[i].0) {
+            continue;
+        }
         let subnet = match network {
             Network::Local => {
                 if subnet.is_some() {
@@ -212,92 +344,148 @@ async fn execute_initialize_components(
             subnet.map(|p| p.to_text())
         );
 
-        let res = call_init_in(&wallet, Principal::from_text(comp_id)?, &network, &subnet).await;
-        if let Err(e) = res {
-            if force && e.to_string().contains(ALREADY_INIT_IN_PANIC_MSG) {
-                warn!(
-                    log,
-                    "init_in has been executed, process continues: {} ({})", name, comp_id
-                );
-            } else {
-                bail!(e);
-            }
-        }
-
-        info!(log, "Called init_in: {} ({})", name, comp_id);
+        let res = call_init_in(
+            &wallet,
+            Principal::from_text(comp_id)?,
+            &network,
+            &subnet,
+            &cycles_managements[name],
+        )
+        .await;
+        summaries[i].init = called_status(
+            log,
+            res,
+            "init_in",
+            ALREADY_INIT_IN_PANIC_MSG,
+            name,
+            comp_id,
+            tolerates_already_executed(force, &states[i].0),
+        )?;
     }
 
     // exec: setup
-    let project_manifest = ProjectManifestData::load(&format!(
-        "{}/{}",
-        &project_path_str, PROJECT_MANIFEST_FILENAME
-    ))?;
-    let component_path_mapping: BTreeMap<String, (ComponentType, String)> = project_manifest
-        .components
-        .iter()
-        .map(|c| {
-            let c_path = format!("{}/{}", &project_path_str, c.component_path);
-            let c_type = ComponentTypeInManifest::determine_type(&c_path)
-                .unwrap_or_else(|_| panic!("Failed to determine component type: {}", &c_path));
-            let id = Path::new(&c_path).file_stem().unwrap().to_str().unwrap();
-            (id.to_owned(), (c_type, c_path))
-        })
-        .collect();
-    for (name, comp_id) in &components {
+    for (i, (name, comp_id)) in components.iter().enumerate() {
+        if !to_execute(Phase::Setup, &states[i].1) {
+            continue;
+        }
+        if summaries[i].is_blocked(Phase::Setup) {
+            info!(
+                log,
+                "Skip calling setup, the phases before it are not done: {} ({})", name, comp_id
+            );
+            continue;
+        }
         let (component_type, component_path) = component_path_mapping
             .get(name.as_str())
             .context(format!("Component not found: {}", &name))?;
-        let generator = codegen::generator(*component_type, component_path, name)?;
+        let generator =
+            codegen::generator(*component_type, project_path_str, component_path, name)?;
 
         if let Some(raw_args) = generator.generate_component_setup_args(&network, &comp_id_mgr)? {
             info!(log, "Calling setup: {} ({})", name, comp_id);
-
             let res = call_setup(&wallet, Principal::from_text(comp_id)?, raw_args).await;
-            if let Err(e) = res {
-                if force && e.to_string().contains(ALREADY_SETUP_PANIC_MSG) {
-                    warn!(
-                        log,
-                        "setup has been executed, process continues: {} ({})", name, comp_id
-                    );
-                } else {
-                    bail!(e);
-                }
-            }
-
-            info!(log, "Called setup: {} ({})", name, comp_id);
-        } else {
-            info!(log, "Skip calling setup: {} ({})", name, comp_id);
-        };
+            summaries[i].setup = called_status(
+                log,
+                res,
+                "setup",
+                ALREADY_SETUP_PANIC_MSG,
+                name,
+                comp_id,
+                tolerates_already_executed(force, &states[i].1),
+            )?;
+        }
     }
 
     // exec: set_task
-    for (name, comp_id) in &components {
+    for (i, (name, comp_id)) in components.iter().enumerate() {
+        if !to_execute(Phase::Task, &states[i].2) {
+            continue;
+        }
+        if summaries[i].is_blocked(Phase::Task) {
+            info!(
+                log,
+                "Skip calling set_task, the phases before it are not done: {} ({})", name, comp_id
+            );
+            continue;
+        }
         let (component_type, component_path) = component_path_mapping
             .get(name.as_str())
             .context(format!("Component not found: {}", &name))?;
-        let generator = codegen::generator(*component_type, component_path, name)?;
+        let generator =
+            codegen::generator(*component_type, project_path_str, component_path, name)?;
         if let Some(args) = generator.manifest().timer_settings() {
             info!(log, "Calling set_task: {} ({})", name, comp_id);
             let res = call_set_task(&wallet, Principal::from_text(comp_id)?, &args).await;
-            if let Err(e) = res {
-                if force && e.to_string().contains(ALREADY_SET_TASK_PANIC_MSG) {
-                    warn!(
-                        log,
-                        "set_task has been executed, process continues: {} ({})", name, comp_id
-                    );
-                } else {
-                    bail!(e);
-                }
+            summaries[i].task = called_status(
+                log,
+                res,
+                "set_task",
+                ALREADY_SET_TASK_PANIC_MSG,
+                name,
+                comp_id,
+                tolerates_already_executed(force, &states[i].2),
+            )?;
+            if summaries[i].task != PhaseStatus::Executed {
+                continue;
+            }
+            summaries[i].task_interval_sec = Some(args.interval_sec);
+            if args.schedule.is_some() {
+                let next = args
+                    .next_fire_times(now_sec(), PREVIEW_COUNT)
+                    .into_iter()
+                    .map(format_utc)
+                    .collect::<Vec<_>>();
+                info!(
+                    log,
+                    "Next runs: {} ({}): {}",
+                    name,
+                    comp_id,
+                    next.join(", ")
+                );
             }
-            info!(log, "Called set_task: {} ({})", name, comp_id);
-        } else {
-            info!(log, "Skip calling set_task: {} ({})", name, comp_id);
         }
     }
 
+    info!(log, "Summary:");
+    for line in format_summary(&summaries) {
+        info!(log, "  {}", line);
+    }
+
     Ok(())
 }
 
+// Whether the panic of being already executed is tolerated: if forced, or if the state couldn't be queried
+// and the phase is called to find it out
+fn tolerates_already_executed(force: bool, state: &PhaseState) -> bool {
+    force || matches!(state, PhaseState::Unknown(_))
+}
+
+// Status of the phase after calling it, tolerating the panic of being already executed if `tolerated`
+fn called_status(
+    log: &Logger,
+    res: anyhow::Result<()>,
+    method: &str,
+    already_executed_msg: &str,
+    name: &str,
+    comp_id: &str,
+    tolerated: bool,
+) -> anyhow::Result<PhaseStatus> {
+    match res {
+        Ok(()) => {
+            info!(log, "Called {}: {} ({})", method, name, comp_id);
+            Ok(PhaseStatus::Executed)
+        }
+        Err(e) if tolerated && e.to_string().contains(already_executed_msg) => {
+            warn!(
+                log,
+                "{} has been executed, process continues: {} ({})", method, name, comp_id
+            );
+            Ok(PhaseStatus::Done)
+        }
+        Err(e) => bail!(e),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -316,12 +504,23 @@ mod tests {
                 project_name: Some(project_name.to_string()),
                 no_samples: false,
                 example: None,
+                chain_id: Some(11155111),
             },
         );
     }
     fn tear_down(project_name: &str) {
         fs::remove_dir_all(project_name).unwrap();
     }
+    #[test]
+    fn test_tolerates_already_executed() {
+        assert!(tolerates_already_executed(
+            false,
+            &PhaseState::Unknown("no query method".to_string())
+        ));
+        assert!(!tolerates_already_executed(false, &PhaseState::Pending));
+        assert!(tolerates_already_executed(true, &PhaseState::Pending));
+    }
+
     #[test]
     fn test_exec() {
         let project_name = "exec_test_exec";
@@ -340,6 +539,7 @@ mod tests {
                         network: Network::Local,
                         subnet: None,
                         port: None,
+                        only: None,
                         force: false,
                     },
                 );
@@ -0,0 +1,254 @@
+use std::fmt;
+
+use anyhow::Context;
+use candid::{CandidType, Decode, Deserialize, Encode, Principal};
+
+/// Query of components for whether setup has been called
+const SETUP_STATE_QUERY: &str = "is_setup";
+/// Query of components for the interval of the running task, 0 if not started
+const TASK_STATE_QUERY: &str = "get_indexing_interval_secs";
+
+/// Phase of exec, in the order of execution
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Phase {
+    /// init_in, creating the proxy, vault and db of the component
+    Init,
+    /// setup, configuring the datasource of the component
+    Setup,
+    /// set_task, starting the periodic task of the component
+    Task,
+}
+
+/// State of a phase of a deployed component, queried before calling it
+#[derive(Clone, Debug, PartialEq)]
+pub enum PhaseState {
+    Done,
+    Pending,
+    /// The component has nothing to call in the phase
+    NotApplicable,
+    /// The state can't be queried, with the reason. The phase is called as without the state
+    Unknown(String),
+}
+
+/// Status of a phase of a component after exec, shown in the summary
+#[derive(Clone, Debug, PartialEq)]
+pub enum PhaseStatus {
+    /// Done before this exec
+    Done,
+    Executed,
+    /// Not done, and not selected by --only
+    Pending,
+    NotApplicable,
+    /// State unknown, and not selected by --only
+    Unknown,
+}
+
+impl PhaseStatus {
+    /// Status of a phase not called in this exec
+    pub fn from_state(state: &PhaseState) -> Self {
+        match state {
+            PhaseState::Done => Self::Done,
+            PhaseState::Pending => Self::Pending,
+            PhaseState::NotApplicable => Self::NotApplicable,
+            PhaseState::Unknown(_) => Self::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for PhaseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Done => "done",
+            Self::Executed => "executed",
+            Self::Pending => "pending",
+            Self::NotApplicable => "-",
+            Self::Unknown => "unknown",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Row of the summary of exec
+pub struct ComponentSummary {
+    pub name: String,
+    pub init: PhaseStatus,
+    pub setup: PhaseStatus,
+    pub task: PhaseStatus,
+    /// Interval of the running task
+    pub task_interval_sec: Option<u32>,
+}
+
+impl ComponentSummary {
+    /// Whether a phase before `phase` is not done, so that `phase` can't be executed
+    pub fn is_blocked(&self, phase: Phase) -> bool {
+        let earlier = match phase {
+            Phase::Init => vec![],
+            Phase::Setup => vec![&self.init],
+            Phase::Task => vec![&self.init, &self.setup],
+        };
+        earlier
+            .iter()
+            .any(|status| *status == &PhaseStatus::Pending)
+    }
+}
+
+/// Table of the status of each phase per component
+pub fn format_summary(rows: &[ComponentSummary]) -> Vec<String> {
+    let header = ["COMPONENT", "INIT", "SETUP", "TASK"].map(String::from);
+    let mut cells = vec![header];
+    for row in rows {
+        let task = match (&row.task, row.task_interval_sec) {
+            (PhaseStatus::Done | PhaseStatus::Executed, Some(interval)) => {
+                format!("{} (every {}s)", row.task, interval)
+            }
+            (task, _) => task.to_string(),
+        };
+        cells.push([
+            row.name.clone(),
+            row.init.to_string(),
+            row.setup.to_string(),
+            task,
+        ]);
+    }
+    let widths = (0..4)
+        .map(|i| cells.iter().map(|c| c[i].len()).max().unwrap_or_default())
+        .collect::<Vec<_>>();
+    cells
+        .iter()
+        .map(|c| {
+            c.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+/// Done once init_in has created the proxy of the component
+pub async fn query_init_state(agent: &ic_agent::Agent, canister: &Principal) -> PhaseState {
+    // NOTE: get_proxy is an update method without side effects, so it can be called as a query
+    match query::<Principal>(agent, canister, "get_proxy").await {
+        Ok(proxy) if proxy == Principal::anonymous() => PhaseState::Pending,
+        Ok(_) => PhaseState::Done,
+        Err(e) => PhaseState::Unknown(e.to_string()),
+    }
+}
+
+/// Done once setup has been called
+pub async fn query_setup_state(agent: &ic_agent::Agent, canister: &Principal) -> PhaseState {
+    setup_state(query::<bool>(agent, canister, SETUP_STATE_QUERY).await)
+}
+
+/// Done once the task has been started, with the interval it runs at
+pub async fn query_task_state(
+    agent: &ic_agent::Agent,
+    canister: &Principal,
+) -> (PhaseState, Option<u32>) {
+    task_state(query::<u32>(agent, canister, TASK_STATE_QUERY).await)
+}
+
+fn setup_state(res: anyhow::Result<bool>) -> PhaseState {
+    match res {
+        Ok(true) => PhaseState::Done,
+        Ok(false) => PhaseState::Pending,
+        Err(e) => PhaseState::Unknown(e.to_string()),
+    }
+}
+
+fn task_state(res: anyhow::Result<u32>) -> (PhaseState, Option<u32>) {
+    match res {
+        Ok(0) => (PhaseState::Pending, None),
+        Ok(interval_sec) => (PhaseState::Done, Some(interval_sec)),
+        Err(e) => (PhaseState::Unknown(e.to_string()), None),
+    }
+}
+
+async fn query<T>(agent: &ic_agent::Agent, canister: &Principal, method: &str) -> anyhow::Result<T>
+where
+    T: CandidType + for<'de> Deserialize<'de>,
+{
+    let res = agent
+        .query(canister, method)
+        .with_arg(Encode!()?)
+        .call()
+        .await?;
+    Decode!(res.as_slice(), T).with_context(|| format!("invalid response of {}", method))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_blocked() {
+        let summary = ComponentSummary {
+            name: "sample".to_string(),
+            init: PhaseStatus::Done,
+            setup: PhaseStatus::Pending,
+            task: PhaseStatus::Unknown,
+            task_interval_sec: None,
+        };
+        assert!(!summary.is_blocked(Phase::Init));
+        assert!(!summary.is_blocked(Phase::Setup));
+        assert!(summary.is_blocked(Phase::Task));
+
+        let summary = ComponentSummary {
+            init: PhaseStatus::Pending,
+            setup: PhaseStatus::NotApplicable,
+            ..summary
+        };
+        assert!(summary.is_blocked(Phase::Setup));
+        assert!(summary.is_blocked(Phase::Task));
+    }
+
+    #[test]
+    fn test_setup_state() {
+        assert_eq!(setup_state(Ok(true)), PhaseState::Done);
+        assert_eq!(setup_state(Ok(false)), PhaseState::Pending);
+        assert_eq!(
+            setup_state(Err(anyhow::anyhow!("no query method"))),
+            PhaseState::Unknown("no query method".to_string())
+        );
+    }
+
+    #[test]
+    fn test_task_state() {
+        assert_eq!(task_state(Ok(3600)), (PhaseState::Done, Some(3600)));
+        assert_eq!(task_state(Ok(0)), (PhaseState::Pending, None));
+        assert_eq!(
+            task_state(Err(anyhow::anyhow!("no query method"))),
+            (PhaseState::Unknown("no query method".to_string()), None)
+        );
+    }
+
+    #[test]
+    fn test_format_summary() {
+        let rows = vec![
+            ComponentSummary {
+                name: "sample_snapshot_indexer_evm".to_string(),
+                init: PhaseStatus::Done,
+                setup: PhaseStatus::Executed,
+                task: PhaseStatus::Executed,
+                task_interval_sec: Some(3600),
+            },
+            ComponentSummary {
+                name: "sample_lens".to_string(),
+                init: PhaseStatus::Pending,
+                setup: PhaseStatus::NotApplicable,
+                task: PhaseStatus::NotApplicable,
+                task_interval_sec: None,
+            },
+        ];
+        assert_eq!(
+            format_summary(&rows),
+            vec![
+                "COMPONENT                    INIT     SETUP     TASK",
+                "sample_snapshot_indexer_evm  done     executed  executed (every 3600s)",
+                "sample_lens                  pending  -         -",
+            ]
+        );
+    }
+}
@@ -6,15 +6,19 @@ use std::{fs, path::Path};
 
 use anyhow::{bail, Ok};
 use clap::Parser;
-use slog::{debug, info, Logger};
+use slog::{debug, info, warn, Logger};
 
 use crate::lib::codegen::components::codegen::CodeGenerator;
-use crate::lib::codegen::components::common::GeneratedCodes;
+use crate::lib::codegen::components::common::{ComponentManifest, GeneratedCodes};
 use crate::lib::codegen::templates::{
     accessors_cargo_toml, bindings_cargo_toml, canister_project_cargo_toml, logic_cargo_toml,
     root_cargo_toml,
 };
+use crate::lib::network::{is_offline, is_offline_error};
+use crate::lib::utils::cron::{format_utc, now_sec, PREVIEW_COUNT};
 use crate::lib::utils::env::cache_envfile;
+use crate::lib::utils::rpc::{check_rpc_endpoint, is_rpc_endpoint_mismatch, HttpRpcClient};
+use crate::lib::utils::url::{check_ipv6_reachability, DnsResolver, Ipv6Policy, SystemDnsResolver};
 use crate::lib::utils::{find_duplicates, paths, DOTENV_FILENAME};
 use crate::lib::{
     codegen::project::ProjectManifestData,
@@ -35,11 +39,18 @@ pub struct GenerateOpts {
     /// If not specified, the current directory is targeted.
     #[arg(long, short = 'p')]
     path: Option<String>,
+
+    /// How to treat URLs in manifests without IPv6 addresses, which HTTPS outcalls cannot reach.
+    #[arg(long, value_enum, default_value_t, env = "CSX_IPV6_POLICY")]
+    ipv6_policy: Ipv6Policy,
 }
 
 impl GenerateOpts {
     pub fn new(path: Option<String>) -> Self {
-        Self { path }
+        Self {
+            path,
+            ipv6_policy: Ipv6Policy::default(),
+        }
     }
 }
 
@@ -89,7 +100,7 @@ pub fn exec(env: &EnvironmentImpl, opts: GenerateOpts) -> anyhow::Result<()> {
 
     let component_data = project_manifest.load_code_generator(project_path_str.as_str())?;
 
-    exec_codegen(log, &project_path_str, &component_data)?;
+    exec_codegen(log, &project_path_str, &component_data, opts.ipv6_policy)?;
 
     info!(
         log,
@@ -102,6 +113,7 @@ fn exec_codegen(
     log: &Logger,
     project_path_str: &str,
     generators: &Vec<Box<dyn CodeGenerator>>,
+    ipv6_policy: Ipv6Policy,
 ) -> anyhow::Result<()> {
     // generate workspace
     let src_path_str = &paths::src_path_str(project_path_str);
@@ -123,6 +135,7 @@ fn exec_codegen(
     }
 
     // generate canister projects
+    let rpc_client = HttpRpcClient::default();
     let mut generated_component_ids = vec![];
     let mut is_exist_accessors_folder = false;
     let mut is_exist_bindings_folder = false;
@@ -133,27 +146,30 @@ fn exec_codegen(
         if let Err(msg) = manifest.validate_manifest() {
             bail!(format!(r#"[{}] Invalid manifest: {}"#, id, msg));
         }
+        check_rpc_endpoints(log, &id, manifest.as_ref(), &rpc_client)?;
+        check_outcall_urls(log, &id, manifest.as_ref(), ipv6_policy, &SystemDnsResolver)?;
+        preview_schedule(log, &id, manifest.as_ref());
         info!(log, r#"[{}] Start processing..."#, id);
 
         // Processes about interface
         // - copy and move any necessary interfaces to canister
         // - get ethabi::Contract for codegen
-        let mut interface_contract: Option<ethabi::Contract> = None;
-        if let Some(interface_file) = manifest.required_interface() {
+        let mut interfaces: BTreeMap<String, ethabi::Contract> = BTreeMap::new();
+        for interface_file in manifest.required_interfaces() {
             let dst_interface_path_str = format!("{}/{}", &interfaces_path_str, &interface_file);
             let dst_interface_path = Path::new(&dst_interface_path_str);
 
             let user_if_file_path_str =
                 format!("{}/interfaces/{}", project_path_str, &interface_file);
             let user_if_file_path = Path::new(&user_if_file_path_str);
-            if user_if_file_path.exists() {
+            let contract: ethabi::Contract = if user_if_file_path.exists() {
                 fs::copy(user_if_file_path, dst_interface_path)?;
                 info!(
                     log,
                     r#"[{}] Interface file '{}' copied from user's interface"#, id, &interface_file
                 );
                 let abi_file = File::open(user_if_file_path)?;
-                interface_contract = Some(ethabi::Contract::load(abi_file)?);
+                ethabi::Contract::load(abi_file)?
             } else if let Some(contents) = builtin_interface(&interface_file) {
                 fs::write(dst_interface_path, contents)?;
                 info!(
@@ -162,14 +178,17 @@ fn exec_codegen(
                     id,
                     &interface_file
                 );
-                let contract: ethabi::Contract = serde_json::from_str(contents)?;
-                interface_contract = Some(contract);
+                serde_json::from_str(contents)?
             } else {
                 bail!(format!(
                     r#"[{}] Interface file "{}" not found"#,
                     id, &interface_file
                 ));
-            }
+            };
+            interfaces.insert(interface_file, contract);
+        }
+        if let Err(msg) = manifest.validate_with_interfaces(&interfaces) {
+            bail!(format!(r#"[{}] Invalid manifest: {}"#, id, msg));
         }
 
         // copy and move oracle interface
@@ -211,7 +230,7 @@ fn exec_codegen(
                 r#"[{}] Skip creating logic project: '{}' already exists"#, id, logic_path_str,
             );
         } else {
-            let codes = generator.generate_user_impl_template();
+            let codes = generator.generate_user_impl_template_with_interfaces(&interfaces);
             let src = match codes {
                 anyhow::Result::Ok(codes) => {
                     Some(CargoProjectSrc::new_with_mods(BTreeMap::from([
@@ -286,8 +305,9 @@ fn exec_codegen(
         )?;
         // Generate /canisters/(component)
         let canister_path_str = &paths::canisters_path_str(src_path_str, &id);
-        let GeneratedCodes { lib, types } =
-            generator.generate_code(interface_contract).map_err(|err| {
+        let GeneratedCodes { lib, types } = generator
+            .generate_code_with_interfaces(&interfaces)
+            .map_err(|err| {
                 anyhow::anyhow!(r#"[{}] Failed to generate canister code by: {}"#, id, err)
             })?;
         let src = if let Some(types) = types {
@@ -335,6 +355,107 @@ fn exec_codegen(
     anyhow::Ok(())
 }
 
+// Check the URLs are reachable by HTTPS outcalls, which only connect over IPv6
+fn check_outcall_urls(
+    log: &Logger,
+    id: &str,
+    manifest: &dyn ComponentManifest,
+    policy: Ipv6Policy,
+    resolver: &dyn DnsResolver,
+) -> anyhow::Result<()> {
+    if policy == Ipv6Policy::Off {
+        return Ok(());
+    }
+    if is_offline() {
+        warn!(
+            log,
+            r#"[{}] Skip checking IPv6 reachability of URLs in offline mode"#, id
+        );
+        return Ok(());
+    }
+    for (field, url) in manifest.outcall_urls() {
+        if let Err(msg) = check_ipv6_reachability(resolver, &url) {
+            match policy {
+                Ipv6Policy::Error => bail!(format!(
+                    r#"[{}] Invalid manifest: {} is not reachable by HTTPS outcalls: {}"#,
+                    id, field, msg
+                )),
+                _ => warn!(
+                    log,
+                    r#"[{}] {} is not reachable by HTTPS outcalls: {}"#, id, field, msg
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+// Show when the task runs if the timer is given by a schedule
+fn preview_schedule(log: &Logger, id: &str, manifest: &dyn ComponentManifest) {
+    let settings = match manifest.timer_settings() {
+        Some(settings) => settings,
+        None => return,
+    };
+    if let Some(schedule) = &settings.schedule {
+        let next = settings
+            .next_fire_times(now_sec(), PREVIEW_COUNT)
+            .into_iter()
+            .map(format_utc)
+            .collect::<Vec<_>>();
+        info!(
+            log,
+            r#"[{}] Schedule '{}' runs every {} sec with delay_sec {} from the rounded timing, if set now, next at: {}"#,
+            id,
+            schedule,
+            settings.interval_sec,
+            settings.delay_sec.unwrap_or(0),
+            next.join(", ")
+        );
+    }
+}
+
+// Check the RPC endpoints serve the chains in the manifest, and report their latency.
+// Only an endpoint responding for another chain or without the contracts invalidates the manifest,
+// an unreachable or failing endpoint is just warned about
+fn check_rpc_endpoints(
+    log: &Logger,
+    id: &str,
+    manifest: &dyn ComponentManifest,
+    client: &HttpRpcClient,
+) -> anyhow::Result<()> {
+    for endpoint in manifest.rpc_endpoints() {
+        match check_rpc_endpoint(client, &endpoint) {
+            anyhow::Result::Ok(_) if client.is_offline() => info!(
+                log,
+                r#"[{}] RPC endpoint of {} checked with cached responses"#, id, endpoint.field
+            ),
+            anyhow::Result::Ok(report) => info!(
+                log,
+                r#"[{}] RPC endpoint of {} responded in {}ms"#,
+                id,
+                endpoint.field,
+                report.latency.as_millis()
+            ),
+            Err(msg) if is_rpc_endpoint_mismatch(&msg) => bail!(format!(
+                r#"[{}] Invalid manifest: invalid {}: {}"#,
+                id, endpoint.field, msg
+            )),
+            Err(msg) if is_offline_error(&msg) => warn!(
+                log,
+                r#"[{}] Skip checking RPC endpoint of {}: {}"#, id, endpoint.field, msg
+            ),
+            Err(msg) => warn!(
+                log,
+                r#"[{}] RPC endpoint of {} is unavailable, the component may fail to call it: {:#}"#,
+                id,
+                endpoint.field,
+                msg
+            ),
+        }
+    }
+    Ok(())
+}
+
 fn builtin_interface(name: &str) -> Option<&'static str> {
     let interface = match name {
         "ERC20.json" => include_str!("../../resources/ERC20.json"),
@@ -4,7 +4,7 @@ use std::{
     path::Path,
 };
 
-use anyhow::{bail, ensure, Ok};
+use anyhow::{bail, ensure, Context, Ok};
 use clap::Parser;
 use flate2::read::GzDecoder;
 use inflector::cases::titlecase::to_title_case;
@@ -13,13 +13,14 @@ use tar::Archive;
 
 use crate::lib::{
     codegen::{
+        chains::ChainRegistry,
         components::{
             algorithm_indexer::{
                 AlgorithmIndexerComponentManifest, AlgorithmIndexerDatasource,
                 AlgorithmIndexerOutput,
             },
             algorithm_lens::{AlgorithmLensComponentManifest, AlgorithmLensDataSource},
-            common::{ComponentManifest, DatasourceForCanister},
+            common::{ComponentManifest, DatasourceForCanister, DestinationType},
             event_indexer::{EventIndexerComponentManifest, EventIndexerDatasource},
             relayer::{DestinationField, RelayerComponentManifest},
             snapshot_indexer_evm::{
@@ -34,6 +35,7 @@ use crate::lib::{
         templates::gitignore,
     },
     environment::EnvironmentImpl,
+    network::{network_access, HttpRequest},
     utils::{
         CHAINSIGHT_FILENAME, DOTENV_FILENAME, GITIGNORE_FILENAME, PROJECT_MANIFEST_FILENAME,
         PROJECT_MANIFEST_VERSION,
@@ -59,6 +61,11 @@ pub struct NewOpts {
     /// Skip generation of sample component manifests.
     #[arg(long, visible_short_alias = 'n')]
     pub no_samples: bool,
+
+    /// Specifies the chain id of the destination of the sample relayer.
+    /// The sample relayer is generated only if specified.
+    #[arg(long)]
+    pub chain_id: Option<u32>,
 }
 
 pub fn exec(env: &EnvironmentImpl, opts: NewOpts) -> anyhow::Result<()> {
@@ -83,7 +90,12 @@ pub fn exec(env: &EnvironmentImpl, opts: NewOpts) -> anyhow::Result<()> {
         create_project_by_example(trimmed_example, opts.project_name)
     } else {
         info!(log, r#"Start creating new project '{}'..."#, project_name);
-        create_project(&project_path_str, project_name, opts.no_samples)
+        create_project(
+            &project_path_str,
+            project_name,
+            opts.no_samples,
+            opts.chain_id,
+        )
     };
 
     match res {
@@ -95,6 +107,12 @@ pub fn exec(env: &EnvironmentImpl, opts: NewOpts) -> anyhow::Result<()> {
                     log,
                     "You can add components with:\n\n  cd {} && csx add\n", project_name
                 );
+            } else if opts.example.is_none() && opts.chain_id.is_none() {
+                info!(
+                    log,
+                    "You can add a relayer with:\n\n  cd {} && csx add --type relayer --chain-id <CHAIN_ID>\n",
+                    project_name
+                );
             }
             Ok(())
         }
@@ -107,7 +125,12 @@ pub fn exec(env: &EnvironmentImpl, opts: NewOpts) -> anyhow::Result<()> {
     }
 }
 
-fn create_project(project_path: &str, project_name: &str, no_samples: bool) -> anyhow::Result<()> {
+fn create_project(
+    project_path: &str,
+    project_name: &str,
+    no_samples: bool,
+    chain_id: Option<u32>,
+) -> anyhow::Result<()> {
     // Create directories
     fs::create_dir_all(format!("{}/components", project_path))?;
     fs::create_dir_all(format!("{}/interfaces", project_path))?;
@@ -120,7 +143,7 @@ fn create_project(project_path: &str, project_name: &str, no_samples: bool) -> a
     fs::write(format!("{}/{}", project_path, CHAINSIGHT_FILENAME), "")?;
 
     if !no_samples {
-        return create_sample_components(project_path, project_name, "sample");
+        return create_sample_components(project_path, project_name, "sample", chain_id);
     }
 
     fs::write(
@@ -135,6 +158,7 @@ fn create_sample_components(
     project_path: &str,
     project_name: &str,
     component_prefix: &str,
+    chain_id: Option<u32>,
 ) -> anyhow::Result<()> {
     let event_indexer_id = format!("{}_event_indexer", component_prefix);
     let event_indexer_path = format!("components/{}.yaml", event_indexer_id);
@@ -150,22 +174,23 @@ fn create_sample_components(
     let algorithm_lens_path = format!("components/{}.yaml", algorithm_lens_id);
     let snapshot_indexer_https_id = format!("{}_snapshot_indexer_https", component_prefix);
     let snapshot_indexer_https_path = format!("components/{}.yaml", snapshot_indexer_https_id);
+    let components = [
+        Some(&event_indexer_path),
+        Some(&algorithm_indexer_path),
+        Some(&snapshot_indexer_evm_path),
+        Some(&snapshot_indexer_icp_path),
+        chain_id.map(|_| &relayer_path),
+        Some(&algorithm_lens_path),
+        Some(&snapshot_indexer_https_path),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|path| ProjectManifestComponentField::new(path, None))
+    .collect::<Vec<_>>();
     fs::write(
         format!("{}/{}", project_path, PROJECT_MANIFEST_FILENAME),
-        ProjectManifestData::new(
-            project_name,
-            PROJECT_MANIFEST_VERSION,
-            &[
-                ProjectManifestComponentField::new(&event_indexer_path, None),
-                ProjectManifestComponentField::new(&algorithm_indexer_path, None),
-                ProjectManifestComponentField::new(&snapshot_indexer_evm_path, None),
-                ProjectManifestComponentField::new(&snapshot_indexer_icp_path, None),
-                ProjectManifestComponentField::new(&relayer_path, None),
-                ProjectManifestComponentField::new(&algorithm_lens_path, None),
-                ProjectManifestComponentField::new(&snapshot_indexer_https_path, None),
-            ],
-        )
-        .to_str_as_yaml()?,
+        ProjectManifestData::new(project_name, PROJECT_MANIFEST_VERSION, &components)
+            .to_str_as_yaml()?,
     )?;
     fs::write(
         format!("{}/{}", project_path, event_indexer_path),
@@ -183,10 +208,12 @@ fn create_sample_components(
         format!("{}/{}", project_path, snapshot_indexer_icp_path),
         template_snapshot_indexer_icp_manifest(&snapshot_indexer_icp_id).to_str_as_yaml()?,
     )?;
-    fs::write(
-        format!("{}/{}", project_path, relayer_path),
-        template_relayer_manifest(&relayer_id).to_str_as_yaml()?,
-    )?;
+    if let Some(chain_id) = chain_id {
+        fs::write(
+            format!("{}/{}", project_path, relayer_path),
+            template_relayer_manifest(&relayer_id, chain_id).to_str_as_yaml()?,
+        )?;
+    }
     fs::write(
         format!("{}/{}", project_path, algorithm_lens_path),
         template_algorithm_lens_manifest(&algorithm_lens_id).to_str_as_yaml()?,
@@ -259,14 +286,18 @@ fn template_snapshot_indexer_https_manifest(id: &str) -> SnapshotIndexerHTTPSCom
     )
 }
 
-fn template_relayer_manifest(id: &str) -> RelayerComponentManifest {
+fn template_relayer_manifest(id: &str, chain_id: u32) -> RelayerComponentManifest {
     RelayerComponentManifest::new(
         id,
         &to_title_case(id),
         "",
         PROJECT_MANIFEST_VERSION,
         DatasourceForCanister::default(),
-        DestinationField::default(),
+        DestinationField::from_registry(
+            &ChainRegistry::bundled(),
+            chain_id,
+            DestinationType::Uint256,
+        ),
         3600,
     )
 }
@@ -322,12 +353,11 @@ fn download_and_extract(
     }
 
     // Download the .tar.gz archive
-    let response = ureq::get(repo_url).call()?;
+    let content = network_access()
+        .send(&HttpRequest::get(repo_url))
+        .context("Failed to download the examples")?;
 
     let mut file = File::create(tar_gz_path)?;
-    let mut reader = response.into_reader();
-    let mut content = Vec::new();
-    reader.read_to_end(&mut content)?;
     file.write_all(&content)?;
 
     // Decompress and extract the specified folder
@@ -385,13 +415,14 @@ mod tests {
     }
 
     const COMPONENT_PREFIX: &str = "sample";
+    const CHAIN_ID: u32 = 11155111;
 
     #[test]
     fn test_create_project() {
         let project_name = "new_test_create_project";
         run_with_teardown(
             || {
-                let created = create_project(project_name, project_name, false);
+                let created = create_project(project_name, project_name, false, Some(CHAIN_ID));
                 assert!(created.is_ok());
                 assert!(Path::new(project_name).exists());
                 assert!(Path::new(&format!("{}/{}", project_name, CHAINSIGHT_FILENAME)).exists());
@@ -424,11 +455,38 @@ mod tests {
         )
     }
     #[test]
+    fn test_create_project_without_chain_id() {
+        let project_name = "new_test_create_project_without_chain_id";
+        run_with_teardown(
+            || {
+                let created = create_project(project_name, project_name, false, None);
+                assert!(created.is_ok());
+                assert!(Path::new(&format!(
+                    "{}/components/{}_event_indexer.yaml",
+                    project_name, COMPONENT_PREFIX
+                ))
+                .exists());
+                assert!(!Path::new(&format!(
+                    "{}/components/{}_relayer.yaml",
+                    project_name, COMPONENT_PREFIX
+                ))
+                .exists());
+                let project_manifest =
+                    fs::read_to_string(format!("{}/{}", project_name, PROJECT_MANIFEST_FILENAME))
+                        .unwrap();
+                assert!(!project_manifest.contains("relayer"));
+            },
+            || {
+                teardown(project_name);
+            },
+        )
+    }
+    #[test]
     fn test_create_project_without_samples() {
         let project_name = "new_test_create_project_without_samples";
         run_with_teardown(
             || {
-                let created = create_project(project_name, project_name, true);
+                let created = create_project(project_name, project_name, true, None);
                 assert!(created.is_ok());
                 assert!(Path::new(project_name).exists());
                 assert!(Path::new(&format!("{}/{}", project_name, CHAINSIGHT_FILENAME)).exists());
@@ -458,6 +516,7 @@ mod tests {
                     project_name: Some(project_name.to_string()),
                     example: None,
                     no_samples: false,
+                    chain_id: Some(CHAIN_ID),
                 };
                 let res = exec(env, opts);
                 assert!(res.is_ok());
@@ -517,6 +576,8 @@ mod tests {
     #[test]
     fn test_manifest_snapshot_relayer() {
         let id = format!("{}_relayer", COMPONENT_PREFIX);
-        assert_display_snapshot!(template_relayer_manifest(&id).to_str_as_yaml().unwrap());
+        assert_display_snapshot!(template_relayer_manifest(&id, CHAIN_ID)
+            .to_str_as_yaml()
+            .unwrap());
     }
 }
@@ -1,12 +1,18 @@
-use anyhow::{ensure, Context};
+use std::collections::BTreeMap;
+
+use anyhow::{bail, ensure, Context};
 use chainsight_cdk::config::components::{
     AlgorithmIndexerConfig, AlgorithmInputType, AlgorithmOutputType,
 };
+use ethabi::{Contract, Event};
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, quote};
 
 use crate::{
-    lib::codegen::components::algorithm_indexer::AlgorithmIndexerComponentManifest,
+    lib::codegen::{
+        canisters::event_indexer::find_event,
+        components::algorithm_indexer::{AlgorithmIndexerComponentManifest, InputType},
+    },
     types::ComponentType,
 };
 
@@ -61,13 +67,90 @@ pub fn generate_codes(manifest: &AlgorithmIndexerComponentManifest) -> anyhow::R
     custom_codes(manifest).map(|code| code.to_string())
 }
 
-pub fn generate_app(manifest: &AlgorithmIndexerComponentManifest) -> anyhow::Result<String> {
+// Event of the local event indexer in the abi, whose name is the same as the input struct
+fn source_event<'a>(
+    manifest: &AlgorithmIndexerComponentManifest,
+    interfaces: &'a BTreeMap<String, Contract>,
+) -> anyhow::Result<Option<&'a Event>> {
+    let indexer = match manifest.source_event_indexer() {
+        Some(indexer) => indexer,
+        None => return Ok(None),
+    };
+    let name = &manifest.datasource.input.name;
+    let event = find_event(interfaces, &indexer.datasource.event)?;
+    if &event.name == name {
+        return Ok(Some(event));
+    }
+    bail!(
+        "event {} is not indexed by {}",
+        name,
+        manifest.datasource.principal
+    )
+}
+
+// Fields of the input struct to receive the event from the event indexer
+fn event_input_fields(event: &Event) -> anyhow::Result<BTreeMap<String, String>> {
+    event
+        .inputs
+        .iter()
+        .map(|p| {
+            let ty = InputType::from_param_type(&p.kind).with_context(|| {
+                format!(
+                    "type of {}.{} ({}) can not be inferred. specify datasource.input.fields",
+                    event.name, p.name, p.kind
+                )
+            })?;
+            Ok((p.name.clone(), ty.type_path().to_string()))
+        })
+        .collect()
+}
+
+fn validate_input_fields(fields: &BTreeMap<String, String>, event: &Event) -> anyhow::Result<()> {
+    for (name, ty) in fields {
+        let param = event
+            .inputs
+            .iter()
+            .find(|p| &p.name == name)
+            .with_context(|| {
+                let names = event.inputs.iter().map(|p| p.name.as_str());
+                format!(
+                    "field {} is not in event {}. available fields: {}",
+                    name,
+                    event.name,
+                    names.collect::<Vec<_>>().join(", ")
+                )
+            })?;
+        // NOTE: fields of the types which can not be inferred are left to the user
+        if let Some(expected) = InputType::from_param_type(&param.kind) {
+            ensure!(
+                InputType::from_type_path(ty) == Some(expected.clone()),
+                "type of field {} must be {} for {}, but {}",
+                name,
+                expected.type_path(),
+                param.kind,
+                ty
+            );
+        }
+    }
+    Ok(())
+}
+
+pub fn generate_app(
+    manifest: &AlgorithmIndexerComponentManifest,
+    interfaces: &BTreeMap<String, Contract>,
+) -> anyhow::Result<String> {
     let input_type = input_type_ident(manifest);
     let event_struct = format_ident!("{}", &manifest.datasource.input.name);
 
-    let event_interfaces = &manifest.datasource.input.fields;
+    let event_interfaces = match &manifest.datasource.input.fields {
+        Some(fields) => Some(fields.clone()),
+        None => match source_event(manifest, interfaces)? {
+            Some(event) => Some(event_input_fields(event)?),
+            None => None,
+        },
+    };
 
-    let (input_field_idents, input_field_types) = if let Some(fields) = event_interfaces {
+    let (input_field_idents, input_field_types) = if let Some(fields) = &event_interfaces {
         (
             fields
                 .iter()
@@ -188,10 +271,71 @@ pub fn validate_manifest(manifest: &AlgorithmIndexerComponentManifest) -> anyhow
     Ok(())
 }
 
+pub fn validate_with_interfaces(
+    manifest: &AlgorithmIndexerComponentManifest,
+    interfaces: &BTreeMap<String, Contract>,
+) -> anyhow::Result<()> {
+    let event = match source_event(manifest, interfaces)? {
+        Some(event) => event,
+        None => return Ok(()),
+    };
+    match &manifest.datasource.input.fields {
+        Some(fields) => validate_input_fields(fields, event)
+            .context("datasource.input.fields does not match the event of the source"),
+        None => event_input_fields(event).map(|_| ()),
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::fs::File;
+
     use super::*;
 
+    fn transfer() -> Event {
+        let contract = Contract::load(File::open("resources/ERC20.json").unwrap()).unwrap();
+        contract.event("Transfer").unwrap().clone()
+    }
+
+    #[test]
+    fn test_event_input_fields() {
+        assert_eq!(
+            event_input_fields(&transfer()).unwrap(),
+            BTreeMap::from([
+                ("from".to_string(), "String".to_string()),
+                ("to".to_string(), "String".to_string()),
+                (
+                    "value".to_string(),
+                    "chainsight_cdk::core::U256".to_string()
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_input_fields() {
+        let fields = |fields: &[(&str, &str)]| {
+            fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let event = transfer();
+        assert!(validate_input_fields(
+            &fields(&[("from", "String"), ("value", "chainsight_cdk::core::U256")]),
+            &event
+        )
+        .is_ok());
+        let err = validate_input_fields(&fields(&[("amount", "String")]), &event).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("available fields: from, to, value"));
+        let err = validate_input_fields(&fields(&[("value", "String")]), &event).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("must be chainsight_cdk::core::U256 for uint256"));
+    }
+
     #[test]
     fn test_convert_struct_path_to_token_stream() {
         assert_eq!(
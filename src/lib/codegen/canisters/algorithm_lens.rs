@@ -1,14 +1,17 @@
+use std::collections::BTreeMap;
+
 use crate::{
     lib::{
         codegen::components::{
             algorithm_lens::{AlgorithmLensComponentManifest, AlgorithmLensDataSource},
             common::ComponentManifest,
+            utils::{candid_type_to_rust, parse_candid_type},
         },
         utils::paths,
     },
     types::ComponentType,
 };
-use anyhow::ensure;
+use anyhow::{ensure, Context};
 use chainsight_cdk::{
     config::components::{AlgorithmLensConfig, LENS_FUNCTION_RESPONSE_TYPE},
     convert::candid::CanisterMethodIdentifier,
@@ -39,12 +42,15 @@ pub fn generate_app(manifest: &AlgorithmLensComponentManifest) -> anyhow::Result
     let id = manifest.id().ok_or(anyhow::anyhow!("id is required"))?;
     let AlgorithmLensComponentManifest {
         datasource: AlgorithmLensDataSource { methods, .. },
-        with_args,
         ..
     } = manifest;
 
-    let call_func_templates = methods.iter().enumerate().map(|(i, m)| {
-        let method_identifier = CanisterMethodIdentifier::new(&m.identifier).expect("method_identifier parse error");
+    let identifiers = methods
+        .iter()
+        .map(|m| m.resolved_identifier())
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let call_func_templates = methods.iter().zip(&identifiers).enumerate().map(|(i, (m, identifier))| {
+        let method_identifier = CanisterMethodIdentifier::new(identifier).expect("method_identifier parse error");
         // NOTE: Because prefix 'get' is added by macro
         let getter = format_ident!("get_{}", m.func_name_alias.as_ref().map_or_else(
             || generate_default_label_for_query_call(&method_identifier.identifier, &m.id),
@@ -75,19 +81,23 @@ pub fn generate_app(manifest: &AlgorithmLensComponentManifest) -> anyhow::Result
             }
         };
 
-        if with_args.is_some() && with_args.unwrap() {
-            // add argument type that is implemented fields by user
+        if manifest.is_with_args() {
+            // add argument type with the fields in the manifest, or implemented fields by user
             let args_type_ident = format_ident!(
                 "{}",
                 AlgorithmLensComponentManifest::CALCULATE_ARGS_STRUCT_NAME
             );
+            let args_fields = match &manifest.args {
+                Some(args) => calculate_args_fields(args)?,
+                None => vec![quote! { pub dummy: u64 }],
+            };
 
             quote! {
                 #base_quote
 
                 #[derive(Clone, Debug,  Default, candid::CandidType, serde::Deserialize, serde::Serialize)]
                 pub struct #args_type_ident {
-                    pub dummy: u64
+                    #(#args_fields),*
                 }
 
                 pub async fn calculate(targets: Vec<String>, args: #args_type_ident) -> #output_type_ident {
@@ -110,6 +120,21 @@ pub fn generate_app(manifest: &AlgorithmLensComponentManifest) -> anyhow::Result
     Ok(code.to_string())
 }
 
+// Fields of CalculateArgs with rust types of the candid types in the manifest
+fn calculate_args_fields(args: &BTreeMap<String, String>) -> anyhow::Result<Vec<TokenStream>> {
+    args.iter()
+        .map(|(name, ty)| {
+            let (env, candid_ty) =
+                parse_candid_type(ty).with_context(|| format!("invalid type of args.{}", name))?;
+            let rust_ty = candid_type_to_rust(&env, &candid_ty)
+                .with_context(|| format!("invalid type of args.{}", name))?;
+            let name = syn::parse_str::<syn::Ident>(name)
+                .map_err(|_| anyhow::anyhow!("args.{} is not a valid field name", name))?;
+            Ok(quote! { pub #name: #rust_ty })
+        })
+        .collect()
+}
+
 pub fn generate_dependencies_accessor(
     manifest: &AlgorithmLensComponentManifest,
 ) -> anyhow::Result<String> {
@@ -123,9 +148,15 @@ pub fn generate_dependencies_accessor(
             out.unwrap().0
         }
     };
-    let call_funcs = methods
-        .iter()
-        .map(|m| generate_query_call(&m.id, &m.identifier, m.func_name_alias.as_deref()));
+    let mut call_funcs = vec![];
+    for m in methods.iter() {
+        let identifier = m.resolved_identifier()?;
+        call_funcs.push(generate_query_call(
+            &m.id,
+            &identifier,
+            m.func_name_alias.as_deref(),
+        ));
+    }
 
     let bindings_ident = format_ident!("{}", paths::bindings_name(&manifest.id().unwrap()));
     let code = quote! {
@@ -141,6 +172,18 @@ pub fn validate_manifest(manifest: &AlgorithmLensComponentManifest) -> anyhow::R
         manifest.metadata.type_ == ComponentType::AlgorithmLens,
         "type is not AlgorithmLens"
     );
+    for (i, method) in manifest.datasource.methods.iter().enumerate() {
+        method
+            .resolved_identifier()
+            .map_err(|e| anyhow::anyhow!("datasource.methods[{}]: {}", i, e))?;
+    }
+    if let Some(args) = &manifest.args {
+        ensure!(
+            manifest.with_args != Some(false),
+            "args are specified, but with_args is false"
+        );
+        calculate_args_fields(args)?;
+    }
 
     Ok(())
 }
@@ -1,16 +1,22 @@
-use anyhow::ensure;
+use std::collections::BTreeMap;
+
+use anyhow::{bail, ensure, Context};
 use chainsight_cdk::config::components::EventIndexerConfig;
+use ethabi::{Contract, Event};
 use quote::quote;
 
 use crate::{
-    lib::{
-        codegen::components::event_indexer::EventIndexerComponentManifest,
-        utils::url::{is_supporting_ipv6_url, is_valid_rpc_url},
+    lib::codegen::components::{
+        event_indexer::{EventIndexerComponentManifest, EventIndexerEventDefinition},
+        utils::is_valid_evm_address,
     },
     types::ComponentType,
 };
 
-pub fn generate_codes(manifest: &EventIndexerComponentManifest) -> anyhow::Result<String> {
+pub fn generate_codes(
+    manifest: &EventIndexerComponentManifest,
+    interfaces: &BTreeMap<String, Contract>,
+) -> anyhow::Result<String> {
     ensure!(
         manifest.metadata.type_ == ComponentType::EventIndexer,
         "type is not EventIndexer"
@@ -24,6 +30,60 @@ pub fn generate_codes(manifest: &EventIndexerComponentManifest) -> anyhow::Resul
     Ok(code.to_string())
 }
 
+pub(crate) fn find_event<'a>(
+    interfaces: &'a BTreeMap<String, Contract>,
+    event: &EventIndexerEventDefinition,
+) -> anyhow::Result<&'a Event> {
+    let interface = event.interface.clone().unwrap_or_default();
+    let contract = interfaces
+        .get(&interface)
+        .with_context(|| format!("interface {} is not loaded", interface))?;
+    find_event_by_name(contract, &event.identifier)
+        .with_context(|| format!("invalid event identifier in {}", interface))
+}
+
+/// Canonical signature of the event, e.g. `Transfer(address,address,uint256)`
+fn event_signature(event: &Event) -> String {
+    let params = event
+        .inputs
+        .iter()
+        .map(|p| p.kind.to_string())
+        .collect::<Vec<_>>();
+    format!("{}({})", event.name, params.join(","))
+}
+
+/// Find the event by the name, which the SDK looks up in the abi
+pub fn find_event_by_name<'a>(contract: &'a Contract, name: &str) -> anyhow::Result<&'a Event> {
+    let events = contract
+        .events_by_name(name)
+        .map_err(|_| anyhow::anyhow!("event {} is not found", name))?;
+    match events.as_slice() {
+        [event] => Ok(event),
+        // NOTE: the pinned chainsight-cdk identifies the event only by the name
+        _ => bail!(
+            "overloaded event {} is not supported by the current chainsight-cdk yet: {}",
+            name,
+            events
+                .iter()
+                .map(event_signature)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Validate the event against the abi
+pub fn validate_with_interfaces(
+    manifest: &EventIndexerComponentManifest,
+    interfaces: &BTreeMap<String, Contract>,
+) -> anyhow::Result<()> {
+    find_event(interfaces, &manifest.datasource.event)?;
+    Ok(())
+}
+
+// eth_getLogs of most RPC providers limits the block range
+const MAX_BATCH_SIZE: u64 = 10000;
+
 pub fn validate_manifest(manifest: &EventIndexerComponentManifest) -> anyhow::Result<()> {
     ensure!(
         manifest.metadata.type_ == ComponentType::EventIndexer,
@@ -34,10 +94,58 @@ pub fn validate_manifest(manifest: &EventIndexerComponentManifest) -> anyhow::Re
         manifest.datasource.event.interface.is_some(),
         "datasource.event.interface is not set"
     );
+    ensure!(
+        is_valid_evm_address(&manifest.datasource.id),
+        "invalid contract address: {}",
+        manifest.datasource.id
+    );
 
-    let rpc_url = &manifest.datasource.network.rpc_url;
-    // is_supporting_ipv6_url(rpc_url)?;
-    is_valid_rpc_url(rpc_url)?;
+    if let Some(batch_size) = manifest.datasource.batch_size {
+        ensure!(
+            (1..=MAX_BATCH_SIZE).contains(&batch_size),
+            "datasource.batch_size must be between 1 and {}",
+            MAX_BATCH_SIZE
+        );
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn erc20() -> Contract {
+        Contract::load(File::open("resources/ERC20.json").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_find_event_by_name() {
+        let contract = erc20();
+        assert_eq!(
+            find_event_by_name(&contract, "Transfer").unwrap(),
+            contract.event("Transfer").unwrap()
+        );
+        assert!(find_event_by_name(&contract, "Mint").is_err());
+    }
+
+    #[test]
+    fn test_find_overloaded_event() {
+        let abi = r#"[
+            {"type": "event", "name": "Sent", "anonymous": false, "inputs": [
+                {"name": "to", "type": "address", "indexed": true}
+            ]},
+            {"type": "event", "name": "Sent", "anonymous": false, "inputs": [
+                {"name": "to", "type": "address", "indexed": true},
+                {"name": "data", "type": "bytes", "indexed": false}
+            ]}
+        ]"#;
+        let contract: Contract = serde_json::from_str(abi).unwrap();
+        let err = find_event_by_name(&contract, "Sent").unwrap_err();
+        assert!(err.to_string().contains("Sent(address,bytes)"));
+    }
+}
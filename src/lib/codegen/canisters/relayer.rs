@@ -1,23 +1,25 @@
 use anyhow::ensure;
+use candid::{
+    types::{Label, Type, TypeInner},
+    TypeEnv,
+};
 use chainsight_cdk::{
-    config::components::{RelayerConfig, LENS_FUNCTION_ARGS_TYPE},
-    convert::candid::CanisterMethodIdentifier,
+    config::components::RelayerConfig, convert::candid::CanisterMethodIdentifier,
     web3::ContractFunction,
 };
 use ethabi::{Param, ParamType};
+use inflector::cases::snakecase::to_snake_case;
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
 
 use crate::{
-    lib::{
-        codegen::components::{
-            algorithm_lens::AlgorithmLensComponentManifest,
-            relayer::RelayerComponentManifest,
-            utils::{generate_method_identifier, get_did_by_component_id, is_lens_with_args},
-        },
+    lib::codegen::components::{
+        common::DestinationType,
+        relayer::{DestinationField, RelayerComponentManifest},
         utils::{
-            paths::bindings_name,
-            url::{is_supporting_ipv6_url, is_valid_rpc_url},
+            candid_method_type, encode_canister_method_args, encode_lens_calculate_args,
+            generate_call_args_fn, generate_lens_call_args, generate_method_identifier,
+            get_did_by_component_id, is_lens_with_args,
         },
     },
     types::ComponentType,
@@ -105,7 +107,17 @@ pub fn generate_codes(manifest: &RelayerComponentManifest) -> anyhow::Result<Str
     Ok(code.to_string())
 }
 
-fn custom_converter(manifest: &RelayerComponentManifest) -> TokenStream {
+fn default_filter() -> TokenStream {
+    quote! {
+        pub fn filter(_: &CallCanisterResponse) -> bool {
+            true
+        }
+    }
+}
+
+// `convert` and `filter` of the logic, called by the relayer canister.
+// Responses which cannot be converted into the call args are filtered out instead of relayed
+fn custom_converter(manifest: &RelayerComponentManifest) -> (TokenStream, TokenStream) {
     let config: RelayerConfig = manifest.clone().into();
     let contract_function = ContractFunction::new(
         "src/".to_owned() + &config.abi_file_path,
@@ -113,17 +125,214 @@ fn custom_converter(manifest: &RelayerComponentManifest) -> TokenStream {
     );
 
     match contract_function.call_args().len() {
-        0 => quote! {},
-        1 => quote! {},
+        0 => (quote! {}, default_filter()),
+        1 => (quote! {}, default_filter()),
         _ => {
             let call = ContractCall::new(contract_function.clone());
             let args_struct = call.call_args_struct();
             let struct_ident = format_ident!("{}", CALL_ARGS_STRUCT_NAME);
-            quote! {
+
+            let method = &manifest.datasource.method;
+            let interface = if method.interface.is_some() {
+                method.interface.clone()
+            } else {
+                get_did_by_component_id(&manifest.datasource.location.id)
+            };
+            // NOTE: if the response type cannot be resolved, every argument is left to the user
+            let (env, response_fields) = match candid_method_type(&method.identifier, &interface) {
+                Ok(method_type) => {
+                    let fields = method_type.response_record_fields();
+                    (method_type.env, fields)
+                }
+                Err(_) => (TypeEnv::new(), vec![]),
+            };
+
+            let values: Vec<Option<TokenStream>> = call
+                .call_args()
+                .iter()
+                .map(|param| {
+                    let name = normalize_field_name(&param.name);
+                    response_fields
+                        .iter()
+                        .find(|f| match f.id.as_ref() {
+                            Label::Named(label) => normalize_field_name(label) == name,
+                            _ => false,
+                        })
+                        .and_then(|f| convert_field(&env, &name, &f.ty, &param.kind))
+                })
+                .collect();
+            if values.iter().all(|v| v.is_none()) {
+                let converter = quote! {
+                    #args_struct
+                    pub fn convert(_: &CallCanisterResponse) -> #struct_ident {
+                        todo!()
+                    }
+                };
+                return (converter, default_filter());
+            }
+
+            let values = values
+                .into_iter()
+                .zip(call.call_args())
+                .map(|(value, param)| {
+                    value.unwrap_or_else(|| {
+                        let msg = format!(
+                            "TODO: no compatible field in CallCanisterResponse for `{}` ({})",
+                            param.name, param.kind
+                        );
+                        quote! { todo!(#msg) }
+                    })
+                });
+            let converter = quote! {
                 #args_struct
-                pub fn convert(_: &CallCanisterResponse) -> #struct_ident {
-                    todo!()
+                pub fn try_convert(res: &CallCanisterResponse) -> Result<#struct_ident, String> {
+                    Ok(#struct_ident::new(#(#values),*))
+                }
+                pub fn convert(res: &CallCanisterResponse) -> #struct_ident {
+                    try_convert(res).expect("response which cannot be converted is filtered out")
+                }
+            };
+            let filter = quote! {
+                pub fn filter(res: &CallCanisterResponse) -> bool {
+                    try_convert(res).is_ok()
+                }
+            };
+            (converter, filter)
+        }
+    }
+}
+
+// Field name in the bindings generated from candid, to match with an ABI parameter name
+fn normalize_field_name(name: &str) -> String {
+    to_snake_case(name.trim_start_matches('_'))
+}
+
+// Expression converting a field of the candid response into the ABI parameter,
+// only if the types are compatible
+fn convert_field(
+    env: &TypeEnv,
+    field_name: &str,
+    candid_ty: &Type,
+    kind: &ParamType,
+) -> Option<TokenStream> {
+    let field = format_ident!("{}", field_name);
+    convert_value(env, quote! { res.#field }, field_name, candid_ty, kind)
+}
+
+// Expression converting a candid value into the ABI parameter, only if the types are compatible
+// (nat -> uint, nat/int -> int, text -> string, text/blob -> address, blob -> bytes,
+// bool -> bool, vec -> array). Values out of the range of the parameter are returned as errors
+fn convert_value(
+    env: &TypeEnv,
+    value: TokenStream,
+    label: &str,
+    candid_ty: &Type,
+    kind: &ParamType,
+) -> Option<TokenStream> {
+    let ty = env.trace_type(candid_ty).ok()?;
+    match (ty.as_ref(), kind) {
+        (TypeInner::Nat, ParamType::Uint(_)) => Some(quote! {
+            ic_web3_rs::types::U256::from_dec_str(&#value.0.to_string())
+                .map_err(|e| format!("{}: {:?}", #label, e))?
+        }),
+        (
+            TypeInner::Nat8 | TypeInner::Nat16 | TypeInner::Nat32 | TypeInner::Nat64,
+            ParamType::Uint(_),
+        ) => Some(quote! { ic_web3_rs::types::U256::from(#value) }),
+        (TypeInner::Nat | TypeInner::Int, ParamType::Int(_)) => {
+            Some(to_int256(quote! { #value.0.to_string() }, label))
+        }
+        (
+            TypeInner::Nat8
+            | TypeInner::Nat16
+            | TypeInner::Nat32
+            | TypeInner::Nat64
+            | TypeInner::Int8
+            | TypeInner::Int16
+            | TypeInner::Int32
+            | TypeInner::Int64,
+            ParamType::Int(_),
+        ) => Some(to_int256(quote! { #value.to_string() }, label)),
+        (TypeInner::Text, ParamType::String) => Some(quote! { #value.clone() }),
+        (TypeInner::Text, ParamType::Address) => Some(quote! {
+            #value
+                .parse::<ethabi::Address>()
+                .map_err(|e| format!("{}: {:?}", #label, e))?
+        }),
+        (TypeInner::Bool, ParamType::Bool) => Some(quote! { #value }),
+        (TypeInner::Vec(inner), ParamType::Bytes) => match env.trace_type(inner).ok()?.as_ref() {
+            TypeInner::Nat8 => Some(quote! { #value.to_vec() }),
+            _ => None,
+        },
+        (TypeInner::Vec(inner), ParamType::FixedBytes(size)) => {
+            match env.trace_type(inner).ok()?.as_ref() {
+                TypeInner::Nat8 => Some(with_length(value, *size, label, quote! { v.to_vec() })),
+                _ => None,
+            }
+        }
+        (TypeInner::Vec(inner), ParamType::Address) => match env.trace_type(inner).ok()?.as_ref() {
+            TypeInner::Nat8 => Some(with_length(
+                value,
+                20,
+                label,
+                quote! { ethabi::Address::from_slice(v) },
+            )),
+            _ => None,
+        },
+        (TypeInner::Vec(inner), ParamType::Array(kind)) => {
+            let elem = convert_value(env, quote! { v }, label, inner, kind)?;
+            Some(quote! {
+                #value
+                    .iter()
+                    .cloned()
+                    .map(|v| -> Result<_, String> { Ok(#elem) })
+                    .collect::<Result<Vec<_>, String>>()?
+            })
+        }
+        _ => None,
+    }
+}
+
+// Bytes converted by `converted` from `v`, only if they have the length of the ABI parameter
+fn with_length(
+    value: TokenStream,
+    size: usize,
+    label: &str,
+    converted: TokenStream,
+) -> TokenStream {
+    quote! {
+        {
+            let v = &#value;
+            if v.len() != #size {
+                return Err(format!("{}: {} bytes, expected {}", #label, v.len(), #size));
+            }
+            #converted
+        }
+    }
+}
+
+// Two's complement of the signed decimal string, as int256 is encoded
+fn to_int256(dec_str: TokenStream, label: &str) -> TokenStream {
+    quote! {
+        {
+            let v = #dec_str;
+            let (negative, abs) = match v.strip_prefix('-') {
+                Some(abs) => (true, abs),
+                None => (false, v.as_str()),
+            };
+            let abs = ic_web3_rs::types::U256::from_dec_str(abs)
+                .map_err(|e| format!("{}: {:?}", #label, e))?;
+            let max = ic_web3_rs::types::U256::max_value() >> 1;
+            if negative {
+                if abs > max + ic_web3_rs::types::U256::one() {
+                    return Err(format!("{}: {} is out of the range of int256", #label, v));
                 }
+                (!abs).overflowing_add(ic_web3_rs::types::U256::one()).0
+            } else {
+                if abs > max {
+                    return Err(format!("{}: {} is out of the range of int256", #label, v));
+                }
+                abs
             }
         }
     }
@@ -137,23 +346,11 @@ pub fn generate_app(manifest: &RelayerComponentManifest) -> anyhow::Result<Strin
         get_did_by_component_id(&manifest.datasource.location.id)
     };
     let method_identifier = generate_method_identifier(&method.identifier, &interface)?;
-    let converter = custom_converter(manifest);
+    let (converter, filter) = custom_converter(manifest);
     let call_args_idents = if manifest.lens_targets.is_some() {
         if is_lens_with_args(method_identifier) {
             let id = manifest.id.clone().expect("id is not set");
-            let bindings = format_ident!("{}", bindings_name(&id));
-            let lens_args_ident = format_ident!("{}", LENS_FUNCTION_ARGS_TYPE);
-            let calculate_args_ident = format_ident!(
-                "{}",
-                AlgorithmLensComponentManifest::CALCULATE_ARGS_STRUCT_NAME
-            );
-            quote! {
-                pub type #calculate_args_ident = #bindings::#calculate_args_ident;
-                pub type #lens_args_ident = #bindings::#lens_args_ident;
-                pub fn call_args() -> #calculate_args_ident {
-                    todo!("generate CalculateArgs as args to call")
-                }
-            }
+            generate_lens_call_args(&id, &manifest.datasource.location.id, &method)?
         } else {
             quote! {}
         }
@@ -163,11 +360,16 @@ pub fn generate_app(manifest: &RelayerComponentManifest) -> anyhow::Result<Strin
         if request_args_type.is_some() {
             let request_args_ident =
                 format_ident!("{}", CanisterMethodIdentifier::REQUEST_ARGS_TYPE_NAME);
+            let (method_type, args) =
+                encode_canister_method_args(&manifest.datasource.location.id, &method)?;
+            let call_args_fn = generate_call_args_fn(
+                &format_ident!("CallCanisterArgs"),
+                method_type.func.args.len(),
+                &args,
+            );
             quote! {
                 pub type CallCanisterArgs = types::#request_args_ident;
-                pub fn call_args() -> CallCanisterArgs {
-                    todo!()
-                }
+                #call_args_fn
             }
         } else {
             quote! {
@@ -187,9 +389,7 @@ pub fn generate_app(manifest: &RelayerComponentManifest) -> anyhow::Result<Strin
         pub type CallCanisterResponse = types::#response_ident;
         #call_args_idents
         #converter
-        pub fn filter(_: &CallCanisterResponse) -> bool {
-            true
-        }
+        #filter
     }
     .to_string())
 }
@@ -199,10 +399,213 @@ pub fn validate_manifest(manifest: &RelayerComponentManifest) -> anyhow::Result<
         manifest.metadata.type_ == ComponentType::Relayer,
         "type is not Relayer"
     );
+    // NOTE: args to call a lens are CalculateArgs of the lens
+    let datasource = &manifest.datasource;
+    if manifest.lens_targets.is_none() {
+        encode_canister_method_args(&datasource.location.id, &datasource.method)
+            .map_err(|e| anyhow::anyhow!("invalid datasource.method: {}", e))?;
+    } else {
+        encode_lens_calculate_args(&datasource.location.id, &datasource.method)
+            .map_err(|e| anyhow::anyhow!("invalid datasource.method: {}", e))?;
+    }
+
+    let destination = manifest.resolved_destination();
+    validate_destination_type(&destination)
+        .map_err(|e| anyhow::anyhow!("invalid destination: {}", e))?;
+    validate_resolved_destination(&destination)
+        .map_err(|e| anyhow::anyhow!("invalid destination: {}", e))
+}
 
-    let rpc_url = &manifest.destination.rpc_url;
-    // is_supporting_ipv6_url(rpc_url)?;
-    is_valid_rpc_url(rpc_url)?;
+// oracle_address and rpc_url can be omitted only if the chain registry has them
+fn validate_resolved_destination(destination: &DestinationField) -> anyhow::Result<()> {
+    ensure!(
+        !destination.oracle_address.is_empty(),
+        "oracle_address is required: no {} oracle on chain {} in the chain registry",
+        serde_json::to_string(&destination.type_)?,
+        destination.network_id
+    );
+    ensure!(
+        !destination.rpc_url.is_empty(),
+        "rpc_url is required: no rpc for chain {} in the chain registry",
+        destination.network_id
+    );
+    Ok(())
+}
 
+fn validate_destination_type(destination: &DestinationField) -> anyhow::Result<()> {
+    if destination.type_ == DestinationType::Custom {
+        ensure!(
+            destination.interface.is_some() && destination.method_name.is_some(),
+            "interface and method_name are required for custom oracle"
+        );
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use candid::types::TypeInner;
+
+    use crate::lib::codegen::chains::ChainRegistry;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_field_name() {
+        assert_eq!(normalize_field_name("_value"), "value");
+        assert_eq!(normalize_field_name("roundId"), "round_id");
+        assert_eq!(normalize_field_name("timestamp"), "timestamp");
+    }
+
+    #[test]
+    fn test_convert_field() {
+        let env = TypeEnv::new();
+        assert_eq!(
+            convert_field(&env, "value", &TypeInner::Nat.into(), &ParamType::Uint(256))
+                .unwrap()
+                .to_string(),
+            quote! {
+                ic_web3_rs::types::U256::from_dec_str(&res.value.0.to_string())
+                    .map_err(|e| format!("{}: {:?}", "value", e))?
+            }
+            .to_string()
+        );
+        assert_eq!(
+            convert_field(
+                &env,
+                "timestamp",
+                &TypeInner::Nat64.into(),
+                &ParamType::Uint(64)
+            )
+            .unwrap()
+            .to_string(),
+            quote! { ic_web3_rs::types::U256::from(res.timestamp) }.to_string()
+        );
+        assert_eq!(
+            convert_field(&env, "name", &TypeInner::Text.into(), &ParamType::String)
+                .unwrap()
+                .to_string(),
+            quote! { res.name.clone() }.to_string()
+        );
+        assert_eq!(
+            convert_field(
+                &env,
+                "data",
+                &TypeInner::Vec(TypeInner::Nat8.into()).into(),
+                &ParamType::Bytes
+            )
+            .unwrap()
+            .to_string(),
+            quote! { res.data.to_vec() }.to_string()
+        );
+        assert_eq!(
+            convert_field(
+                &env,
+                "hash",
+                &TypeInner::Vec(TypeInner::Nat8.into()).into(),
+                &ParamType::FixedBytes(32)
+            )
+            .unwrap()
+            .to_string(),
+            with_length(quote! { res.hash }, 32, "hash", quote! { v.to_vec() }).to_string()
+        );
+        assert!(convert_field(
+            &env,
+            "value",
+            &TypeInner::Text.into(),
+            &ParamType::Uint(256)
+        )
+        .is_none());
+        assert!(convert_field(
+            &env,
+            "value",
+            &TypeInner::Vec(TypeInner::Nat.into()).into(),
+            &ParamType::Bytes
+        )
+        .is_none());
+        assert_eq!(
+            convert_field(&env, "price", &TypeInner::Int.into(), &ParamType::Int(256))
+                .unwrap()
+                .to_string(),
+            to_int256(quote! { res.price.0.to_string() }, "price").to_string()
+        );
+        assert_eq!(
+            convert_field(&env, "owner", &TypeInner::Text.into(), &ParamType::Address)
+                .unwrap()
+                .to_string(),
+            quote! {
+                res.owner
+                    .parse::<ethabi::Address>()
+                    .map_err(|e| format!("{}: {:?}", "owner", e))?
+            }
+            .to_string()
+        );
+        assert_eq!(
+            convert_field(
+                &env,
+                "values",
+                &TypeInner::Vec(TypeInner::Nat64.into()).into(),
+                &ParamType::Array(Box::new(ParamType::Uint(256)))
+            )
+            .unwrap()
+            .to_string(),
+            quote! {
+                res.values
+                    .iter()
+                    .cloned()
+                    .map(|v| -> Result<_, String> { Ok(ic_web3_rs::types::U256::from(v)) })
+                    .collect::<Result<Vec<_>, String>>()?
+            }
+            .to_string()
+        );
+        assert!(convert_field(
+            &env,
+            "values",
+            &TypeInner::Vec(TypeInner::Text.into()).into(),
+            &ParamType::Array(Box::new(ParamType::Uint(256)))
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_validate_destination_type() {
+        let mut destination = DestinationField::new(
+            1,
+            DestinationType::Custom,
+            "0x0539a0EF8e5E60891fFf0958A059E049e43020d9".to_string(),
+            "https://eth.llamarpc.com".to_string(),
+        );
+        assert!(validate_destination_type(&destination).is_err());
+        destination.interface = Some("CustomOracle.json".to_string());
+        destination.method_name = Some("update_price".to_string());
+        assert!(validate_destination_type(&destination).is_ok());
+        destination.type_ = DestinationType::Uint256;
+        destination.interface = None;
+        assert!(validate_destination_type(&destination).is_ok());
+    }
+
+    #[test]
+    fn test_validate_resolved_destination() {
+        let registry = ChainRegistry::bundled();
+        let omitted = |network_id: u32, type_: DestinationType| {
+            DestinationField::new(network_id, type_, String::new(), String::new())
+                .resolve(&registry)
+        };
+        let mumbai = omitted(80001, DestinationType::Uint256);
+        assert_eq!(
+            mumbai.oracle_address,
+            "0539a0EF8e5E60891fFf0958A059E049e43020d9"
+        );
+        assert!(validate_resolved_destination(&mumbai).is_ok());
+        // no oracle of the type on the chain
+        assert!(validate_resolved_destination(&omitted(80001, DestinationType::Custom)).is_err());
+        // no oracle on the chain
+        assert!(validate_resolved_destination(&omitted(1, DestinationType::Uint256)).is_err());
+        // unknown chain
+        let mut unknown = omitted(31337, DestinationType::Uint256);
+        unknown.oracle_address = "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512".to_string();
+        assert!(validate_resolved_destination(&unknown).is_err());
+        unknown.rpc_url = "http://localhost:8545".to_string();
+        assert!(validate_resolved_destination(&unknown).is_ok());
+    }
+}
@@ -1,11 +1,15 @@
-use anyhow::ensure;
+use std::collections::BTreeMap;
+
+use anyhow::{bail, ensure, Context};
 use chainsight_cdk::config::components::SnapshotIndexerEVMConfig;
+use ethabi::{ethereum_types::U256, param_type::Reader, Contract, ParamType};
 use quote::quote;
 
 use crate::{
-    lib::{
-        codegen::components::snapshot_indexer_evm::SnapshotIndexerEVMComponentManifest,
-        utils::url::{is_supporting_ipv6_url, is_valid_rpc_url},
+    lib::codegen::components::{
+        common::DatasourceMethod,
+        snapshot_indexer_evm::{yaml_to_json, SnapshotIndexerEVMComponentManifest},
+        utils::{is_checksummed_evm_address, is_valid_evm_address},
     },
     types::ComponentType,
 };
@@ -28,15 +32,379 @@ pub fn generate_app(_manifest: &SnapshotIndexerEVMComponentManifest) -> anyhow::
     Ok(quote! {}.to_string())
 }
 
+/// Method identifier like `balanceOf(address):(uint256)`, return values are optional
+#[derive(Debug, PartialEq)]
+pub struct EvmMethodIdentifier {
+    pub name: String,
+    pub inputs: Vec<ParamType>,
+    pub outputs: Option<Vec<ParamType>>,
+}
+impl EvmMethodIdentifier {
+    pub fn parse(identifier: &str) -> anyhow::Result<Self> {
+        let normalized = identifier.replace(' ', "");
+        let (function, outputs) = match normalized.split_once(':') {
+            Some((function, outputs)) => (function, Some(outputs)),
+            None => (normalized.as_str(), None),
+        };
+        let (name, inputs) = function
+            .find('(')
+            .map(|i| function.split_at(i))
+            .with_context(|| format!("arguments are not specified in {}", identifier))?;
+        ensure!(
+            is_valid_function_name(name),
+            "invalid function name in {}",
+            identifier
+        );
+        let inputs = match Reader::read(inputs) {
+            Ok(ParamType::Tuple(types)) => types,
+            _ => bail!("invalid arguments in {}", identifier),
+        };
+        let outputs = match outputs {
+            Some(outputs) => match Reader::read(outputs) {
+                Ok(ParamType::Tuple(types)) => Some(types),
+                Ok(ty) => Some(vec![ty]),
+                Err(e) => bail!("invalid return values in {}: {}", identifier, e),
+            },
+            None => None,
+        };
+        let types = inputs.iter().chain(outputs.iter().flatten());
+        if let Some(invalid) = types.clone().find(|t| !is_valid_param_type(t)) {
+            bail!("invalid type {} in {}", invalid, identifier);
+        }
+        Ok(Self {
+            name: name.to_string(),
+            inputs,
+            outputs,
+        })
+    }
+
+    fn signature(&self) -> String {
+        let inputs = self.inputs.iter().map(|p| p.to_string());
+        format!("{}({})", self.name, inputs.collect::<Vec<_>>().join(","))
+    }
+}
+
+fn is_valid_function_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+// NOTE: Reader accepts any size of integers and fixed bytes
+fn is_valid_param_type(kind: &ParamType) -> bool {
+    match kind {
+        ParamType::Uint(size) | ParamType::Int(size) => (8..=256).contains(size) && size % 8 == 0,
+        ParamType::FixedBytes(size) => (1..=32).contains(size),
+        ParamType::Array(inner) | ParamType::FixedArray(inner, _) => is_valid_param_type(inner),
+        ParamType::Tuple(types) => types.iter().all(is_valid_param_type),
+        _ => true,
+    }
+}
+
+// Check args in the manifest against the argument types of the method identifier
+fn validate_args(method: &DatasourceMethod) -> anyhow::Result<()> {
+    let identifier = EvmMethodIdentifier::parse(&method.identifier)?;
+    ensure!(
+        method.args.len() == identifier.inputs.len(),
+        "{} takes {} args, but {} given",
+        identifier.signature(),
+        identifier.inputs.len(),
+        method.args.len()
+    );
+    for (i, (kind, value)) in identifier.inputs.iter().zip(&method.args).enumerate() {
+        validate_arg(kind, value, &format!("args[{}]", i))?;
+    }
+    Ok(())
+}
+
+fn validate_arg(kind: &ParamType, value: &serde_yaml::Value, path: &str) -> anyhow::Result<()> {
+    use serde_yaml::Value as Yaml;
+    match (kind, value) {
+        (ParamType::Address, Yaml::String(address)) => {
+            ensure!(
+                is_valid_evm_address(address),
+                "{}: invalid address {}",
+                path,
+                address
+            );
+            ensure!(
+                is_checksummed_evm_address(address),
+                "{}: invalid checksum of address {}",
+                path,
+                address
+            );
+        }
+        (ParamType::Bytes, Yaml::String(bytes)) => {
+            decode_hex(bytes).with_context(|| format!("{}: invalid bytes {}", path, bytes))?;
+        }
+        (ParamType::FixedBytes(size), Yaml::String(bytes)) => {
+            let decoded =
+                decode_hex(bytes).with_context(|| format!("{}: invalid bytes {}", path, bytes))?;
+            ensure!(
+                decoded.len() == *size,
+                "{}: bytes{} must be {} bytes, but {} bytes given",
+                path,
+                size,
+                size,
+                decoded.len()
+            );
+        }
+        (ParamType::Uint(size), _) => validate_integer(value, false, *size, path)?,
+        (ParamType::Int(size), _) => validate_integer(value, true, *size, path)?,
+        (ParamType::Bool, Yaml::Bool(_)) | (ParamType::String, Yaml::String(_)) => {}
+        (ParamType::Array(inner), Yaml::Sequence(values)) => {
+            for (i, value) in values.iter().enumerate() {
+                validate_arg(inner, value, &format!("{}[{}]", path, i))?;
+            }
+        }
+        (ParamType::FixedArray(inner, size), Yaml::Sequence(values)) => {
+            ensure!(
+                values.len() == *size,
+                "{}: {} must have {} elements, but {} given",
+                path,
+                kind,
+                size,
+                values.len()
+            );
+            for (i, value) in values.iter().enumerate() {
+                validate_arg(inner, value, &format!("{}[{}]", path, i))?;
+            }
+        }
+        (ParamType::Tuple(types), Yaml::Sequence(values)) => {
+            ensure!(
+                values.len() == types.len(),
+                "{}: {} must have {} elements, but {} given",
+                path,
+                kind,
+                types.len(),
+                values.len()
+            );
+            for (i, (inner, value)) in types.iter().zip(values).enumerate() {
+                validate_arg(inner, value, &format!("{}[{}]", path, i))?;
+            }
+        }
+        _ => bail!("{}: {:?} is not a value of {}", path, value, kind),
+    }
+    Ok(())
+}
+
+fn decode_hex(value: &str) -> anyhow::Result<Vec<u8>> {
+    let value = value
+        .strip_prefix("0x")
+        .context("hex string must start with 0x")?;
+    Ok(hex::decode(value)?)
+}
+
+// Integers out of the range of 64 bits are lost in yaml, so they must be passed as strings
+fn validate_integer(
+    value: &serde_yaml::Value,
+    signed: bool,
+    size: usize,
+    path: &str,
+) -> anyhow::Result<()> {
+    let (negative, magnitude) = match value {
+        serde_yaml::Value::Number(n) => {
+            if let Some(v) = n.as_u64() {
+                (false, U256::from(v))
+            } else if let Some(v) = n.as_i64() {
+                (v < 0, U256::from(v.unsigned_abs()))
+            } else {
+                bail!(
+                    "{}: {} is not an integer. pass big integers as strings",
+                    path,
+                    n
+                )
+            }
+        }
+        serde_yaml::Value::String(v) => {
+            let (negative, digits) = match v.strip_prefix('-') {
+                Some(digits) => (true, digits),
+                None => (false, v.as_str()),
+            };
+            let magnitude = match digits.strip_prefix("0x") {
+                Some(hex) => U256::from_str_radix(hex, 16).ok(),
+                None => U256::from_dec_str(digits).ok(),
+            }
+            .with_context(|| format!("{}: invalid integer {}", path, v))?;
+            (negative, magnitude)
+        }
+        _ => bail!(
+            "{}: {:?} is not a value of {}",
+            path,
+            value,
+            kind_name(signed, size)
+        ),
+    };
+    let in_range = match (signed, negative) {
+        (false, true) => magnitude.is_zero(),
+        (false, false) => magnitude.bits() <= size,
+        (true, false) => magnitude.bits() < size,
+        // the minimum is -2^(size-1)
+        (true, true) => magnitude.bits() < size || magnitude == U256::one() << (size - 1),
+    };
+    ensure!(
+        in_range,
+        "{}: {} is out of the range of {}",
+        path,
+        yaml_to_json(value.clone()),
+        kind_name(signed, size)
+    );
+    Ok(())
+}
+
+fn kind_name(signed: bool, size: usize) -> String {
+    if signed {
+        format!("int{}", size)
+    } else {
+        format!("uint{}", size)
+    }
+}
+
 pub fn validate_manifest(manifest: &SnapshotIndexerEVMComponentManifest) -> anyhow::Result<()> {
     ensure!(
         manifest.metadata.type_ == ComponentType::SnapshotIndexerEVM,
         "type is not SnapshotIndexerEVM"
     );
 
-    let rpc_url = &manifest.datasource.location.args.rpc_url;
-    // is_supporting_ipv6_url(rpc_url)?;
-    is_valid_rpc_url(rpc_url)?;
+    validate_args(&manifest.datasource.method).context("invalid datasource.method")?;
 
     Ok(())
 }
+
+// Check the method identifiers exist in the abis with the same types
+pub fn validate_with_interfaces(
+    manifest: &SnapshotIndexerEVMComponentManifest,
+    interfaces: &BTreeMap<String, Contract>,
+) -> anyhow::Result<()> {
+    let method = &manifest.datasource.method;
+    let interface = method.interface.clone().unwrap_or_default();
+    let contract = interfaces
+        .get(&interface)
+        .with_context(|| format!("interface {} is not found", interface))?;
+    validate_method_with_interface(&method.identifier, contract)
+        .with_context(|| format!("invalid method identifier for {}", interface))
+}
+
+fn validate_method_with_interface(identifier: &str, contract: &Contract) -> anyhow::Result<()> {
+    let identifier = EvmMethodIdentifier::parse(identifier)?;
+    let functions = contract
+        .functions_by_name(&identifier.name)
+        .map_err(|_| anyhow::anyhow!("function {} is not found", identifier.name))?;
+    let function = functions
+        .iter()
+        .find(|f| {
+            f.inputs
+                .iter()
+                .map(|p| &p.kind)
+                .eq(identifier.inputs.iter())
+        })
+        .with_context(|| {
+            let candidates = functions
+                .iter()
+                .map(|f| f.signature())
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "function {} is not found. candidates: {}",
+                identifier.signature(),
+                candidates
+            )
+        })?;
+    if let Some(outputs) = &identifier.outputs {
+        let expected = function.outputs.iter().map(|p| p.kind.to_string());
+        ensure!(
+            function.outputs.iter().map(|p| &p.kind).eq(outputs.iter()),
+            "return values of {} must be ({})",
+            identifier.signature(),
+            expected.collect::<Vec<_>>().join(",")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn test_evm_method_identifier() {
+        assert_eq!(
+            EvmMethodIdentifier::parse("balanceOf(address):(uint256)").unwrap(),
+            EvmMethodIdentifier {
+                name: "balanceOf".to_string(),
+                inputs: vec![ParamType::Address],
+                outputs: Some(vec![ParamType::Uint(256)]),
+            }
+        );
+        assert_eq!(
+            EvmMethodIdentifier::parse("allowance(address, address)").unwrap(),
+            EvmMethodIdentifier {
+                name: "allowance".to_string(),
+                inputs: vec![ParamType::Address, ParamType::Address],
+                outputs: None,
+            }
+        );
+        assert!(EvmMethodIdentifier::parse("totalSupply").is_err());
+        assert!(EvmMethodIdentifier::parse("1st()").is_err());
+        assert!(EvmMethodIdentifier::parse("totalSupply(uint999)").is_err());
+    }
+
+    #[test]
+    fn test_validate_args() {
+        let method = |identifier: &str, args: &str| DatasourceMethod {
+            identifier: identifier.to_string(),
+            interface: None,
+            args: serde_yaml::from_str(args).unwrap(),
+        };
+        let ok = [
+            ("totalSupply():(uint256)", "[]"),
+            (
+                "balanceOf(address):(uint256)",
+                "['0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed']",
+            ),
+            (
+                "f(uint256,int8,bytes4,bool,uint8[2])",
+                "['115792089237316195423570985008687907853269984665640564039457584007913129639935', -128, '0x01020304', true, [1, 2]]",
+            ),
+            ("f(int256,uint64)", "['-0x10', 18446744073709551615]"),
+        ];
+        for (identifier, args) in ok {
+            assert!(
+                validate_args(&method(identifier, args)).is_ok(),
+                "{}",
+                identifier
+            );
+        }
+        let err = [
+            ("balanceOf(address)", "[]", "takes 1 args, but 0 given"),
+            (
+                "balanceOf(address)",
+                "['0x5aaeb6053F3E94C9b9A09f33669435E7Ef1BeAed']",
+                "invalid checksum",
+            ),
+            ("f(bytes4)", "['0x010203']", "must be 4 bytes"),
+            ("f(uint8)", "[256]", "out of the range of uint8"),
+            ("f(int8)", "[-129]", "out of the range of int8"),
+            ("f(uint256)", "[-1]", "out of the range of uint256"),
+            ("f(uint256)", "[1.0e+30]", "pass big integers as strings"),
+            ("f(uint8[2])", "[[1]]", "must have 2 elements"),
+            ("f(bool)", "['true']", "is not a value of bool"),
+        ];
+        for (identifier, args, msg) in err {
+            let e = validate_args(&method(identifier, args)).unwrap_err();
+            assert!(e.to_string().contains(msg), "{}: {}", identifier, e);
+        }
+    }
+
+    #[test]
+    fn test_validate_method_with_interface() {
+        let contract = Contract::load(File::open("resources/ERC20.json").unwrap()).unwrap();
+        assert!(validate_method_with_interface("totalSupply():(uint256)", &contract).is_ok());
+        assert!(validate_method_with_interface("balanceOf(address)", &contract).is_ok());
+        assert!(validate_method_with_interface("totalSupplies():(uint256)", &contract).is_err());
+        assert!(validate_method_with_interface("balanceOf(uint256)", &contract).is_err());
+        assert!(validate_method_with_interface("totalSupply():(uint8)", &contract).is_err());
+    }
+}
@@ -1,12 +1,20 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
 
-use anyhow::ensure;
+use anyhow::{bail, ensure, Context};
 use chainsight_cdk::{config::components::SnapshotIndexerHTTPSConfig, web2::build_url};
 use quote::quote;
+use serde_json::{Map, Value};
 
 use crate::{
-    lib::codegen::components::snapshot_indexer_https::{
-        SnapshotIndexerHTTPSComponentManifest, SnapshotIndexerHTTPSDataSourceQueries,
+    lib::{
+        codegen::components::snapshot_indexer_https::{
+            ResponseSampleFormat, SnapshotIndexerHTTPSComponentManifest,
+            SnapshotIndexerHTTPSDataSourceQueries, SnapshotIndexerHTTPSResponseSample,
+        },
+        network::{fetch_json, network_access, HttpRequest},
     },
     types::ComponentType,
 };
@@ -26,12 +34,16 @@ pub fn generate_codes(manifest: &SnapshotIndexerHTTPSComponentManifest) -> anyho
 }
 
 pub trait JsonTypeGenStrategy {
+    /// `input` is a JSON sample of the response
     fn generate_code(
         &self,
         struct_name: &str,
-        url: &str,
+        input: &str,
         options: json_typegen_shared::Options,
     ) -> anyhow::Result<String>;
+
+    /// Fetch a response to generate types from
+    fn fetch_sample(&self, url: &str, headers: &BTreeMap<String, String>) -> anyhow::Result<Value>;
 }
 
 pub struct JsonTypeGenStrategyImpl;
@@ -39,12 +51,24 @@ impl JsonTypeGenStrategy for JsonTypeGenStrategyImpl {
     fn generate_code(
         &self,
         struct_name: &str,
-        url: &str,
+        input: &str,
         options: json_typegen_shared::Options,
     ) -> anyhow::Result<String> {
-        json_typegen_shared::codegen(struct_name, url, options)
+        json_typegen_shared::codegen(struct_name, input, options)
             .map_err(|e| anyhow::anyhow!("Failed to generate code by json_typegen_shared: {:?}", e))
     }
+
+    fn fetch_sample(&self, url: &str, headers: &BTreeMap<String, String>) -> anyhow::Result<Value> {
+        // NOTE: fetch the sample here instead of json_typegen, to go through the NetworkAccess
+        let request = HttpRequest {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            headers: headers.clone(),
+            body: None,
+        };
+        fetch_json(network_access().as_ref(), &request)
+            .context("Failed to fetch a response sample, specify datasource.response_sample to generate types without the network")
+    }
 }
 
 pub fn generate_app(
@@ -76,13 +100,17 @@ pub fn generate_app(
             )
         }
     };
-    let url = build_url(&datasource.url, queries);
+    // NOTE: the url is only fetched if no local sample is provided
+    let sample = match &datasource.response_sample {
+        Some(sample) => load_sample_json(sample, manifest.project_root())?,
+        None => strategy.fetch_sample(&build_url(&datasource.url, queries), &datasource.headers)?,
+    };
 
     let mut options = json_typegen_shared::Options::default();
     options.derives = "Debug, Clone, candid::CandidType, candid::Deserialize, serde::Serialize, chainsight_cdk_macros::StableMemoryStorable".into();
     options.import_style = json_typegen_shared::ImportStyle::QualifiedPaths;
     let codes = strategy
-        .generate_code(struct_name, &url, options)
+        .generate_code(struct_name, &serde_json::to_string(&sample)?, options)
         .map_err(|e| anyhow::anyhow!("Failed to generate code by json_typegen_shared: {:?}", e))?;
 
     let comments_for_typegen = r#"// You update the structure as needed.
@@ -94,3 +122,185 @@ pub fn generate_app(
 
     Ok(top_comments + &query_func + comments_for_typegen + use_declares_for_typegen + &codes)
 }
+
+/// Load a JSON sample of the response from the local document specified in the manifest.
+/// JSON Schema and OpenAPI documents are converted to a representative sample.
+pub fn load_sample_json(
+    sample: &SnapshotIndexerHTTPSResponseSample,
+    project_root: &Path,
+) -> anyhow::Result<Value> {
+    let content = std::fs::read_to_string(project_root.join(&sample.path))
+        .with_context(|| format!("Failed to read response sample: {}", sample.path))?;
+    let doc: Value = if sample.path.ends_with(".yaml") || sample.path.ends_with(".yml") {
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse response sample: {}", sample.path))?
+    } else {
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse response sample: {}", sample.path))?
+    };
+    let target = match &sample.pointer {
+        Some(pointer) => doc
+            .pointer(pointer.trim_start_matches('#'))
+            .with_context(|| format!("{} is not found in {}", pointer, sample.path))?,
+        None => &doc,
+    };
+    match sample.format {
+        ResponseSampleFormat::Json => Ok(target.clone()),
+        ResponseSampleFormat::JsonSchema | ResponseSampleFormat::OpenApi => {
+            sample_from_schema(target, &doc, 0)
+        }
+    }
+}
+
+const MAX_SCHEMA_DEPTH: usize = 32;
+
+// Build a JSON value which has the shape described by the schema, to infer types from it
+fn sample_from_schema(schema: &Value, root: &Value, depth: usize) -> anyhow::Result<Value> {
+    if depth > MAX_SCHEMA_DEPTH {
+        // NOTE: recursive definitions are cut off here
+        return Ok(Value::Null);
+    }
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        Value::Bool(_) => return Ok(Value::Null),
+        _ => bail!("Invalid schema: {}", schema),
+    };
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        if !reference.starts_with('#') {
+            bail!("Only local $ref is supported: {}", reference);
+        }
+        let resolved = root
+            .pointer(reference.trim_start_matches('#'))
+            .with_context(|| format!("{} is not found", reference))?;
+        return sample_from_schema(resolved, root, depth + 1);
+    }
+    if let Some(Value::Array(schemas)) = schema.get("allOf") {
+        let mut merged = Map::new();
+        for s in schemas {
+            if let Value::Object(fields) = sample_from_schema(s, root, depth + 1)? {
+                merged.extend(fields);
+            }
+        }
+        return Ok(Value::Object(merged));
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(first) = schema
+            .get(key)
+            .and_then(Value::as_array)
+            .and_then(|s| s.first())
+        {
+            return sample_from_schema(first, root, depth + 1);
+        }
+    }
+
+    let ty = match schema.get("type") {
+        Some(Value::String(ty)) => ty.as_str(),
+        // e.g. ["string", "null"]
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|t| *t != "null")
+            .unwrap_or("null"),
+        _ if schema.contains_key("properties") => "object",
+        _ if schema.contains_key("items") => "array",
+        _ => "",
+    };
+    let value = match ty {
+        "object" => {
+            let mut fields = Map::new();
+            if let Some(Value::Object(props)) = schema.get("properties") {
+                for (name, prop) in props {
+                    fields.insert(name.clone(), sample_from_schema(prop, root, depth + 1)?);
+                }
+            }
+            Value::Object(fields)
+        }
+        "array" => match schema.get("items") {
+            Some(items) => Value::Array(vec![sample_from_schema(items, root, depth + 1)?]),
+            None => Value::Array(vec![]),
+        },
+        "string" => Value::String(String::new()),
+        "integer" => Value::from(0),
+        // NOTE: fractional value to be inferred as a floating point number
+        "number" => Value::from(0.5),
+        "boolean" => Value::Bool(false),
+        _ => Value::Null,
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_sample_from_json_schema() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "dai": { "$ref": "#/definitions/Price" },
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "updated_at": { "type": ["integer", "null"] }
+            },
+            "definitions": {
+                "Price": {
+                    "allOf": [
+                        { "type": "object", "properties": { "usd": { "type": "number" } } },
+                        { "type": "object", "properties": { "active": { "type": "boolean" } } }
+                    ]
+                }
+            }
+        });
+        assert_eq!(
+            sample_from_schema(&schema, &schema, 0).unwrap(),
+            json!({
+                "dai": { "usd": 0.5, "active": false },
+                "tags": [""],
+                "updated_at": 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_sample_from_recursive_schema() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "child": { "$ref": "#" }
+            }
+        });
+        assert!(sample_from_schema(&schema, &schema, 0).is_ok());
+    }
+
+    #[test]
+    fn test_load_sample_json_from_project_root() {
+        let project_root =
+            std::env::temp_dir().join(format!("csx_test_sample_json_{}", std::process::id()));
+        std::fs::create_dir_all(project_root.join("interfaces")).unwrap();
+        std::fs::write(
+            project_root.join("interfaces/price.json"),
+            r#"{ "dai": { "usd": 1.0 } }"#,
+        )
+        .unwrap();
+        let sample = SnapshotIndexerHTTPSResponseSample {
+            path: "interfaces/price.json".to_string(),
+            format: ResponseSampleFormat::Json,
+            pointer: None,
+        };
+        assert_eq!(
+            load_sample_json(&sample, &project_root).unwrap(),
+            json!({ "dai": { "usd": 1.0 } })
+        );
+        assert!(load_sample_json(&sample, Path::new(".")).is_err());
+        std::fs::remove_dir_all(project_root).unwrap();
+    }
+
+    #[test]
+    fn test_sample_from_schema_with_remote_ref() {
+        let schema = json!({ "$ref": "https://example.com/schema.json" });
+        assert!(sample_from_schema(&schema, &schema, 0).is_err());
+    }
+}
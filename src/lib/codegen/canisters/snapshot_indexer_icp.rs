@@ -1,18 +1,17 @@
 use anyhow::ensure;
 use chainsight_cdk::{
-    config::components::{SnapshotIndexerICPConfig, LENS_FUNCTION_ARGS_TYPE},
-    convert::candid::CanisterMethodIdentifier,
+    config::components::SnapshotIndexerICPConfig, convert::candid::CanisterMethodIdentifier,
 };
 use quote::{format_ident, quote};
 
 use crate::{
-    lib::{
-        codegen::components::{
-            algorithm_lens::AlgorithmLensComponentManifest,
-            snapshot_indexer_icp::SnapshotIndexerICPComponentManifest,
-            utils::{generate_method_identifier, get_did_by_component_id, is_lens_with_args},
+    lib::codegen::components::{
+        snapshot_indexer_icp::SnapshotIndexerICPComponentManifest,
+        utils::{
+            encode_canister_method_args, encode_lens_calculate_args, generate_call_args_fn,
+            generate_lens_call_args, generate_method_identifier, get_did_by_component_id,
+            is_lens_with_args,
         },
-        utils::paths::bindings_name,
     },
     types::ComponentType,
 };
@@ -43,20 +42,7 @@ pub fn generate_app(manifest: &SnapshotIndexerICPComponentManifest) -> anyhow::R
     if manifest.lens_targets.is_some() {
         let codes = if is_lens_with_args(method_identifier) {
             let id = manifest.id.clone().expect("id is not set");
-            let bindings = format_ident!("{}", bindings_name(&id));
-            let lens_args_ident = format_ident!("{}", LENS_FUNCTION_ARGS_TYPE);
-            let calculate_args_ident = format_ident!(
-                "{}",
-                AlgorithmLensComponentManifest::CALCULATE_ARGS_STRUCT_NAME
-            );
-
-            quote! {
-                pub type #calculate_args_ident = #bindings::#calculate_args_ident;
-                pub type #lens_args_ident = #bindings::#lens_args_ident;
-                pub fn call_args() -> #calculate_args_ident {
-                    todo!("generate CalculateArgs as args to call")
-                }
-            }
+            generate_lens_call_args(&id, &manifest.datasource.location.id, &method)?
         } else {
             quote! {}
         };
@@ -69,14 +55,19 @@ pub fn generate_app(manifest: &SnapshotIndexerICPComponentManifest) -> anyhow::R
         let request_args_ty_name_def_ident =
             format_ident!("{}", CanisterMethodIdentifier::REQUEST_ARGS_TYPE_NAME);
 
+        let (method_type, args) =
+            encode_canister_method_args(&manifest.datasource.location.id, &method)?;
+        let call_args_fn = generate_call_args_fn(
+            &format_ident!("CallCanisterArgs"),
+            method_type.func.args.len(),
+            &args,
+        );
+
         quote! {
             mod types;
 
             pub type CallCanisterArgs = types::#request_args_ty_name_def_ident;
-            // todo: set return_value by parsing manifest
-            pub fn call_args() -> CallCanisterArgs {
-                todo!()
-            }
+            #call_args_fn
         }
     } else {
         quote! {
@@ -96,9 +87,15 @@ pub fn validate_manifest(manifest: &SnapshotIndexerICPComponentManifest) -> anyh
         "type is not SnapshotIndexerICP"
     );
 
-    // TODO
-    // - check datasource.method.identifier format
-    // - check datasource.method.args length
+    // NOTE: args to call a lens are CalculateArgs of the lens
+    let datasource = &manifest.datasource;
+    if manifest.lens_targets.is_none() {
+        encode_canister_method_args(&datasource.location.id, &datasource.method)
+            .map_err(|e| anyhow::anyhow!("invalid datasource.method: {}", e))?;
+    } else {
+        encode_lens_calculate_args(&datasource.location.id, &datasource.method)
+            .map_err(|e| anyhow::anyhow!("invalid datasource.method: {}", e))?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,164 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::lib::codegen::components::common::DestinationType;
+
+/// File in the project root to add chains to the bundled registry or override them
+pub const CHAINS_FILENAME: &str = "chains.yaml";
+
+/// EVM chain in the chain registry
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Chain {
+    pub chain_id: u32,
+    pub name: String,
+    #[serde(default)]
+    pub rpc_urls: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer_url: Option<String>,
+    /// Chainsight oracle addresses by destination type
+    #[serde(default)]
+    pub oracles: HashMap<DestinationType, String>,
+    #[serde(default)]
+    pub deprecated: bool,
+}
+
+impl Chain {
+    pub fn default_rpc_url(&self) -> Option<&String> {
+        self.rpc_urls.first()
+    }
+
+    pub fn oracle_address(&self, type_: DestinationType) -> Option<&String> {
+        self.oracles.get(&type_)
+    }
+}
+
+/// Registry of EVM chains, bundled with csx and extendable by chains.yaml in the project root
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainRegistry {
+    chains: Vec<Chain>,
+}
+
+impl ChainRegistry {
+    pub fn bundled() -> Self {
+        let chains = serde_yaml::from_str(include_str!("../../../resources/chains.yaml"))
+            .expect("bundled chain registry must be valid");
+        Self { chains }
+    }
+
+    /// Bundled registry extended by chains.yaml in the project root if it exists
+    pub fn load(project_root: &str) -> anyhow::Result<Self> {
+        let mut registry = Self::bundled();
+        let path = Path::new(project_root).join(CHAINS_FILENAME);
+        if path.is_file() {
+            let data = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let chains: Vec<Chain> = serde_yaml::from_str(&data)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            registry.extend(chains);
+        }
+        Ok(registry)
+    }
+
+    /// Add chains, replacing the ones with the same chain_id
+    pub fn extend(&mut self, chains: Vec<Chain>) {
+        for chain in chains {
+            match self
+                .chains
+                .iter_mut()
+                .find(|c| c.chain_id == chain.chain_id)
+            {
+                Some(existing) => *existing = chain,
+                None => self.chains.push(chain),
+            }
+        }
+    }
+
+    pub fn get(&self, chain_id: u32) -> Option<&Chain> {
+        self.chains.iter().find(|c| c.chain_id == chain_id)
+    }
+
+    pub fn chains(&self) -> &[Chain] {
+        &self.chains
+    }
+
+    pub fn oracle_address(&self, chain_id: u32, type_: DestinationType) -> Option<String> {
+        self.get(chain_id)
+            .and_then(|c| c.oracle_address(type_))
+            .cloned()
+    }
+
+    pub fn default_rpc_url(&self, chain_id: u32) -> Option<String> {
+        self.get(chain_id)
+            .and_then(|c| c.default_rpc_url())
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_bundled() {
+        let registry = ChainRegistry::bundled();
+        let ids: HashSet<u32> = registry.chains().iter().map(|c| c.chain_id).collect();
+        assert_eq!(ids.len(), registry.chains().len());
+
+        let mumbai = registry.get(80001).unwrap();
+        assert!(mumbai.deprecated);
+        assert_eq!(
+            registry.oracle_address(80001, DestinationType::Uint256),
+            Some("0539a0EF8e5E60891fFf0958A059E049e43020d9".to_string())
+        );
+        assert_eq!(
+            registry.oracle_address(80001, DestinationType::Custom),
+            None
+        );
+        assert_eq!(
+            registry.default_rpc_url(1),
+            Some("https://eth.llamarpc.com".to_string())
+        );
+        assert!(registry.get(0).is_none());
+    }
+
+    #[test]
+    fn test_load() {
+        let project = "test__chain_registry";
+        fs::create_dir_all(project).unwrap();
+        fs::write(
+            Path::new(project).join(CHAINS_FILENAME),
+            r#"- chain_id: 1
+  name: Ethereum
+  rpc_urls:
+  - http://localhost:8545
+  oracles:
+    uint256: 0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512
+- chain_id: 31337
+  name: Anvil
+"#,
+        )
+        .unwrap();
+        let registry = ChainRegistry::load(project);
+        fs::remove_dir_all(project).unwrap();
+        let registry = registry.unwrap();
+
+        assert_eq!(
+            registry.default_rpc_url(1),
+            Some("http://localhost:8545".to_string())
+        );
+        assert_eq!(
+            registry.oracle_address(1, DestinationType::Uint256),
+            Some("0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512".to_string())
+        );
+        assert_eq!(registry.get(31337).unwrap().name, "Anvil");
+        assert!(registry.get(80001).is_some());
+        assert_eq!(
+            registry.chains().len(),
+            ChainRegistry::bundled().chains().len() + 1
+        );
+    }
+}
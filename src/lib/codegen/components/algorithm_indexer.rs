@@ -6,6 +6,7 @@ use chainsight_cdk::{
     },
     initializer::CycleManagements,
 };
+use ethabi::ParamType;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -19,9 +20,11 @@ use crate::{
 use super::{
     codegen::CodeGenerator,
     common::{
-        custom_tags_interval_sec, ComponentManifest, ComponentMetadata, CycleManagementsManifest,
-        GeneratedCodes, SourceType, Sources, TimerSettings,
+        custom_tags_interval_sec, ComponentManifest, ComponentMetadata, ComponentTypeInManifest,
+        CycleManagementsManifest, GeneratedCodes, SourceType, Sources, TimerSettings,
     },
+    event_indexer::EventIndexerComponentManifest,
+    utils::get_manifest_path_by_component_id,
 };
 
 /// Component Manifest: Algorithm Indexer
@@ -102,12 +105,30 @@ impl AlgorithmIndexerComponentManifest {
                 interval_sec: interval,
                 delay_sec: None,
                 is_round_start_timing: None,
+                schedule: None,
             },
             cycles: None,
         }
     }
 }
 
+impl AlgorithmIndexerComponentManifest {
+    /// Event indexer in the same project which `datasource.principal` refers to
+    pub fn source_event_indexer(&self) -> Option<EventIndexerComponentManifest> {
+        if self.datasource.source_type != AlgorithmInputType::EventIndexer {
+            return None;
+        }
+        let id = &self.datasource.principal;
+        let path = get_manifest_path_by_component_id(id)?;
+        match ComponentTypeInManifest::determine_type(&path) {
+            Ok(ComponentType::EventIndexer) => {
+                EventIndexerComponentManifest::load_with_id(&path, id).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
 pub struct AlgorithmIndexerCodeGenerator {
     manifest: AlgorithmIndexerComponentManifest,
 }
@@ -127,8 +148,14 @@ impl CodeGenerator for AlgorithmIndexerCodeGenerator {
         })
     }
     fn generate_user_impl_template(&self) -> anyhow::Result<GeneratedCodes> {
+        self.generate_user_impl_template_with_interfaces(&BTreeMap::new())
+    }
+    fn generate_user_impl_template_with_interfaces(
+        &self,
+        interfaces: &BTreeMap<String, ethabi::Contract>,
+    ) -> anyhow::Result<GeneratedCodes> {
         Ok(GeneratedCodes {
-            lib: canisters::algorithm_indexer::generate_app(&self.manifest)?,
+            lib: canisters::algorithm_indexer::generate_app(&self.manifest, interfaces)?,
             types: None,
         })
     }
@@ -184,6 +211,19 @@ impl ComponentManifest for AlgorithmIndexerComponentManifest {
         None
     }
 
+    fn required_interfaces(&self) -> Vec<String> {
+        self.source_event_indexer()
+            .map(|indexer| indexer.required_interfaces())
+            .unwrap_or_default()
+    }
+
+    fn validate_with_interfaces(
+        &self,
+        interfaces: &BTreeMap<String, ethabi::Contract>,
+    ) -> anyhow::Result<()> {
+        canisters::algorithm_indexer::validate_with_interfaces(self, interfaces)
+    }
+
     fn get_sources(&self) -> Sources {
         Sources {
             source_type: SourceType::Chainsight,
@@ -205,15 +245,65 @@ impl ComponentManifest for AlgorithmIndexerComponentManifest {
         self.cycles.clone().unwrap_or_default().into()
     }
 }
+/// Type of a field of the input struct
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub enum InputType {
+    U256,
     U128,
     U64,
     U32,
     U16,
     U8,
+    Bool,
     String,
 }
+impl InputType {
+    /// Type of the field to receive an event parameter from the event indexer
+    pub fn from_param_type(kind: &ParamType) -> Option<Self> {
+        match kind {
+            ParamType::Address | ParamType::String => Some(Self::String),
+            ParamType::Bool => Some(Self::Bool),
+            ParamType::Uint(size) => match size {
+                0..=8 => Some(Self::U8),
+                9..=16 => Some(Self::U16),
+                17..=32 => Some(Self::U32),
+                33..=64 => Some(Self::U64),
+                65..=128 => Some(Self::U128),
+                _ => Some(Self::U256),
+            },
+            _ => None,
+        }
+    }
+
+    pub fn from_type_path(path: &str) -> Option<Self> {
+        match path {
+            "chainsight_cdk::core::U256" | "U256" => Some(Self::U256),
+            "u128" => Some(Self::U128),
+            "u64" => Some(Self::U64),
+            "u32" => Some(Self::U32),
+            "u16" => Some(Self::U16),
+            "u8" => Some(Self::U8),
+            "bool" => Some(Self::Bool),
+            "String" => Some(Self::String),
+            _ => None,
+        }
+    }
+
+    /// Rust type of the field
+    pub fn type_path(&self) -> &'static str {
+        match self {
+            Self::U256 => "chainsight_cdk::core::U256",
+            Self::U128 => "u128",
+            Self::U64 => "u64",
+            Self::U32 => "u32",
+            Self::U16 => "u16",
+            Self::U8 => "u8",
+            Self::Bool => "bool",
+            Self::String => "String",
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 
 pub struct InputStruct {
@@ -356,6 +446,7 @@ timer_settings:
                     interval_sec: 3600,
                     delay_sec: None,
                     is_round_start_timing: None,
+                    schedule: None,
                 },
                 cycles: None,
             }
@@ -409,6 +500,7 @@ timer_settings:
                 interval_sec: 3600,
                 delay_sec: None,
                 is_round_start_timing: None,
+                schedule: None,
             },
             cycles: None,
         };
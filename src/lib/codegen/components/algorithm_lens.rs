@@ -16,7 +16,7 @@ use super::{
         ComponentManifest, ComponentMetadata, CycleManagementsManifest, GeneratedCodes, SourceType,
         Sources, TimerSettings,
     },
-    utils::{generate_method_identifier, get_did_by_component_id},
+    utils::{generate_method_identifier, get_did_by_component_id, method_identifier_from_did},
 };
 
 /// Component Manifest: Algorithm Lens
@@ -29,6 +29,9 @@ pub struct AlgorithmLensComponentManifest {
     pub datasource: AlgorithmLensDataSource,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub with_args: Option<bool>,
+    /// Fields of CalculateArgs and their candid types, like `symbol: text`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<BTreeMap<String, String>>,
     pub cycles: Option<CycleManagementsManifest>,
 }
 
@@ -54,15 +57,21 @@ impl AlgorithmLensComponentManifest {
             },
             datasource,
             with_args: None,
+            args: None,
             cycles: None,
         }
     }
+
+    /// Whether calculate takes CalculateArgs, declared by `with_args` or `args`
+    pub fn is_with_args(&self) -> bool {
+        self.with_args.unwrap_or(false) || self.args.is_some()
+    }
 }
 impl From<AlgorithmLensComponentManifest>
     for chainsight_cdk::config::components::AlgorithmLensConfig
 {
     fn from(val: AlgorithmLensComponentManifest) -> Self {
-        let args_type = if val.with_args.is_some() && val.with_args.unwrap() {
+        let args_type = if val.is_with_args() {
             Some(AlgorithmLensComponentManifest::CALCULATE_ARGS_STRUCT_NAME.to_string())
         } else {
             None
@@ -175,14 +184,10 @@ impl ComponentManifest for AlgorithmLensComponentManifest {
         for method in methods {
             let mod_name = method.id.to_string();
 
-            let candid_file_path = if method.candid_file_path.is_some() {
-                method.candid_file_path.clone()
-            } else {
-                get_did_by_component_id(&mod_name)
-            };
-
-            let method_identifier =
-                generate_method_identifier(&method.identifier, &candid_file_path)?;
+            let method_identifier = generate_method_identifier(
+                &method.resolved_identifier()?,
+                &method.candid_file_path(),
+            )?;
             let codes = method_identifier.compile()?;
             bindings.insert(mod_name, codes);
         }
@@ -238,6 +243,31 @@ pub struct AlgorithmLensDataSourceMethod {
     pub func_name_alias: Option<String>,
 }
 
+impl AlgorithmLensDataSourceMethod {
+    /// .did of the target: `candid_file_path`, or the one generated for the component in the project
+    pub fn candid_file_path(&self) -> Option<String> {
+        if self.candid_file_path.is_some() {
+            self.candid_file_path.clone()
+        } else {
+            get_did_by_component_id(&self.id)
+        }
+    }
+
+    /// Identifier with its signature. If only the method name is specified, it is resolved by the .did
+    pub fn resolved_identifier(&self) -> anyhow::Result<String> {
+        let identifier = self.identifier.trim();
+        if identifier.contains(':') {
+            return Ok(self.identifier.clone());
+        }
+        let did_path = self.candid_file_path().ok_or(anyhow::anyhow!(
+            "signature of {} can not be resolved: .did of {} is not found. specify candid_file_path or the identifier with its signature",
+            identifier,
+            self.id
+        ))?;
+        method_identifier_from_did(identifier, &did_path)
+    }
+}
+
 impl Default for AlgorithmLensDataSource {
     fn default() -> Self {
         Self {
@@ -308,6 +338,7 @@ with_args: true
                     }],
                 },
                 with_args: Some(true),
+                args: None,
                 cycles: None,
             }
         );
@@ -321,6 +352,67 @@ with_args: true
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_calculate_args() {
+        let yaml = r#"
+version: v1
+metadata:
+    label: sample_algorithm_lens
+    type: algorithm_lens
+    description: Description
+datasource:
+    methods:
+    - id: last_snapshot
+      identifier: 'get_last_snapshot : () -> (record { value : text; timestamp : nat64 })'
+args:
+    symbol: text
+    limit: opt nat64
+"#;
+        let schema = serde_json::from_str(include_str!(
+            "../../../../resources/schema/algorithm_lens.json"
+        ))
+        .expect("Invalid json");
+        let instance = serde_yaml::from_str(yaml).expect("Invalid yaml");
+        let compiled = JSONSchema::compile(&schema).expect("Invalid schema");
+        assert!(compiled.validate(&instance).is_ok());
+
+        let mut manifest = serde_yaml::from_str::<AlgorithmLensComponentManifest>(yaml).unwrap();
+        manifest.id = Some("sample_algorithm_lens".to_string());
+        assert!(manifest.is_with_args());
+        assert!(manifest.validate_manifest().is_ok());
+
+        let lib = AlgorithmLensCodeGenerator::new(manifest.clone())
+            .generate_user_impl_template()
+            .unwrap()
+            .lib;
+        assert!(lib.contains(
+            &quote::quote! {
+                pub struct CalculateArgs {
+                    pub limit: Option<u64>,
+                    pub symbol: String
+                }
+            }
+            .to_string()
+        ));
+        assert!(lib.contains(
+            &quote::quote! { pub async fn calculate(targets: Vec<String>, args: CalculateArgs) }
+                .to_string()
+        ));
+
+        let mut invalid = manifest.clone();
+        invalid.with_args = Some(false);
+        assert!(invalid.validate_manifest().is_err());
+        let mut invalid = manifest.clone();
+        invalid.args = Some(BTreeMap::from([(
+            "pair".to_string(),
+            "record { base : text }".to_string(),
+        )]));
+        assert!(invalid.validate_manifest().is_err());
+        let mut invalid = manifest;
+        invalid.args = Some(BTreeMap::from([("1st".to_string(), "text".to_string())]));
+        assert!(invalid.validate_manifest().is_err());
+    }
+
     #[test]
     fn test_snapshot_outputs() {
         let manifest = AlgorithmLensComponentManifest {
@@ -343,6 +435,7 @@ with_args: true
                 }],
             },
             with_args: Some(false),
+            args: None,
             cycles: None,
         };
 
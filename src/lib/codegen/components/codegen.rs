@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use anyhow::Result;
 use ethabi::Contract;
 
@@ -24,7 +26,25 @@ use super::{
 
 pub trait CodeGenerator {
     fn generate_code(&self, interface_contract: Option<Contract>) -> Result<GeneratedCodes>;
+    /// Generate codes with all interfaces required by the component, keyed by file name
+    fn generate_code_with_interfaces(
+        &self,
+        interfaces: &BTreeMap<String, Contract>,
+    ) -> Result<GeneratedCodes> {
+        let interface_contract = self
+            .manifest()
+            .required_interface()
+            .and_then(|name| interfaces.get(&name).cloned());
+        self.generate_code(interface_contract)
+    }
     fn generate_user_impl_template(&self) -> anyhow::Result<GeneratedCodes>;
+    /// Generate the user's logic template with all interfaces required by the component
+    fn generate_user_impl_template_with_interfaces(
+        &self,
+        _interfaces: &BTreeMap<String, Contract>,
+    ) -> anyhow::Result<GeneratedCodes> {
+        self.generate_user_impl_template()
+    }
     fn manifest(&self) -> Box<dyn ComponentManifest>;
     fn generate_component_setup_args(
         &self,
@@ -35,6 +55,7 @@ pub trait CodeGenerator {
 
 pub fn generator(
     component_type: ComponentType,
+    project_path: &str,
     component_path: &str,
     id: &str,
 ) -> Result<Box<dyn CodeGenerator>> {
@@ -52,14 +73,16 @@ pub fn generator(
             SnapshotIndexerEVMComponentManifest::load_with_id(component_path, id)?,
         ))),
         ComponentType::Relayer => Ok(Box::new(RelayerCodeGenerator::new(
-            RelayerComponentManifest::load_with_id(component_path, id)?,
+            RelayerComponentManifest::load_with_id(component_path, id)?
+                .with_project_root(project_path)?,
         ))),
         ComponentType::AlgorithmLens => Ok(Box::new(AlgorithmLensCodeGenerator::new(
             AlgorithmLensComponentManifest::load_with_id(component_path, id)?,
         ))),
         ComponentType::SnapshotIndexerHTTPS => {
             Ok(Box::new(SnapshotIndesxerHTTPSCodeGenerator::new(
-                SnapshotIndexerHTTPSComponentManifest::load_with_id(component_path, id)?,
+                SnapshotIndexerHTTPSComponentManifest::load_with_id(component_path, id)?
+                    .with_project_root(project_path),
                 Box::new(JsonTypeGenStrategyImpl),
             )))
         }
@@ -11,12 +11,17 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::{
-    lib::utils::{env::load_env, serializer::ordered_map},
+    lib::utils::{
+        cron::{self, CronSchedule},
+        env::load_env,
+        rpc::RpcEndpoint,
+        serializer::ordered_map,
+    },
     types::ComponentType,
 };
 
 #[allow(clippy::enum_variant_names)]
-#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash, clap::ValueEnum)]
 pub enum DestinationType {
     #[serde(rename = "uint256")]
     Uint256,
@@ -29,6 +34,7 @@ pub enum DestinationType {
     #[serde(rename = "custom")]
     Custom,
 }
+
 #[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
 
 pub enum SourceType {
@@ -161,6 +167,32 @@ pub trait ComponentManifest: std::fmt::Debug {
     /// ex: abi (.json), candid (.candid)
     fn required_interface(&self) -> Option<String>;
 
+    /// Validate the manifest with the interfaces (abi) loaded for this component, keyed by file name
+    fn validate_with_interfaces(
+        &self,
+        _interfaces: &BTreeMap<String, ethabi::Contract>,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Get all interfaces required for this component, in case of using several ones
+    fn required_interfaces(&self) -> Vec<String> {
+        self.required_interface().into_iter().collect()
+    }
+
+    /// EVM JSON-RPC endpoints used by this component, to check they serve the expected chain
+    fn rpc_endpoints(&self) -> Vec<RpcEndpoint> {
+        vec![]
+    }
+
+    /// URLs called by HTTPS outcalls of this component, paired with their fields
+    fn outcall_urls(&self) -> Vec<(String, String)> {
+        self.rpc_endpoints()
+            .into_iter()
+            .map(|e| (e.field, e.url))
+            .collect()
+    }
+
     /// Sources of data provided by this component
     fn get_sources(&self) -> Sources;
 
@@ -218,6 +250,7 @@ impl ComponentTypeInManifest {
 
 /// Settings for Periodic tasks
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "TimerSettingsManifest")]
 pub struct TimerSettings {
     /// Interval in seconds
     pub interval_sec: u32,
@@ -228,6 +261,72 @@ pub struct TimerSettings {
     /// If interval is 60min and this is true, the task will be executed at 00:00, 01:00, 02:00, ...
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_round_start_timing: Option<bool>,
+    /// Cron expression in UTC, translated into the settings above
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+}
+
+impl TimerSettings {
+    /// Next `n` times (unix time in seconds) the task runs if the timer is set at `now`
+    pub fn next_fire_times(&self, now: u64, n: usize) -> Vec<u64> {
+        cron::next_fire_times(
+            now,
+            self.interval_sec,
+            self.delay_sec.unwrap_or(0),
+            self.is_round_start_timing.unwrap_or(false),
+            n,
+        )
+    }
+}
+
+/// TimerSettings as written in manifests, with either interval_sec or schedule
+#[derive(Deserialize)]
+struct TimerSettingsManifest {
+    interval_sec: Option<u32>,
+    delay_sec: Option<u32>,
+    is_round_start_timing: Option<bool>,
+    schedule: Option<String>,
+}
+
+impl TryFrom<TimerSettingsManifest> for TimerSettings {
+    type Error = anyhow::Error;
+
+    fn try_from(val: TimerSettingsManifest) -> anyhow::Result<Self> {
+        let schedule = match val.schedule {
+            Some(schedule) => schedule,
+            None => {
+                let interval_sec = val
+                    .interval_sec
+                    .context("timer_settings requires either interval_sec or schedule")?;
+                return Ok(TimerSettings {
+                    interval_sec,
+                    delay_sec: val.delay_sec,
+                    is_round_start_timing: val.is_round_start_timing,
+                    schedule: None,
+                });
+            }
+        };
+        let timer = CronSchedule::parse(&schedule)
+            .and_then(|s| s.to_rounded_timer())
+            .with_context(|| format!("schedule '{}' can't be run by a periodic timer", schedule))?;
+        if val.interval_sec.unwrap_or(timer.interval_sec) != timer.interval_sec
+            || val.delay_sec.unwrap_or(timer.delay_sec) != timer.delay_sec
+            || val.is_round_start_timing == Some(false)
+        {
+            bail!(
+                "schedule '{}' runs every {} sec with delay_sec {} and is_round_start_timing true, which conflicts with the other timer_settings: remove them or make them match",
+                schedule,
+                timer.interval_sec,
+                timer.delay_sec
+            );
+        }
+        Ok(TimerSettings {
+            interval_sec: timer.interval_sec,
+            delay_sec: (timer.delay_sec > 0).then_some(timer.delay_sec),
+            is_round_start_timing: Some(true),
+            schedule: Some(schedule),
+        })
+    }
 }
 
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -272,3 +371,41 @@ impl From<CycleManagementsManifest> for CycleManagements {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timer_settings_with_schedule() {
+        let settings: TimerSettings = serde_yaml::from_str("schedule: 5 * * * *").unwrap();
+        assert_eq!(
+            settings,
+            TimerSettings {
+                interval_sec: 3600,
+                delay_sec: Some(300),
+                is_round_start_timing: Some(true),
+                schedule: Some("5 * * * *".to_string()),
+            }
+        );
+        // 2024-01-01 10:02:00 UTC
+        assert_eq!(
+            settings.next_fire_times(1704103320, 2),
+            vec![1704107100, 1704110700]
+        );
+
+        let settings: TimerSettings =
+            serde_yaml::from_str("schedule: '@daily'\ninterval_sec: 86400").unwrap();
+        assert_eq!(settings.delay_sec, None);
+        assert!(
+            serde_yaml::from_str::<TimerSettings>("schedule: '@daily'\ninterval_sec: 3600")
+                .is_err()
+        );
+        assert!(serde_yaml::from_str::<TimerSettings>(
+            "schedule: '@daily'\nis_round_start_timing: false"
+        )
+        .is_err());
+        assert!(serde_yaml::from_str::<TimerSettings>("schedule: 0 0 1 * *").is_err());
+        assert!(serde_yaml::from_str::<TimerSettings>("delay_sec: 10").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use anyhow::bail;
 use chainsight_cdk::initializer::CycleManagements;
@@ -8,7 +8,7 @@ use serde_json::json;
 use crate::{
     lib::{
         codegen::{canisters, scripts},
-        utils::component_ids_manager::ComponentIdsManager,
+        utils::{component_ids_manager::ComponentIdsManager, rpc::RpcEndpoint},
     },
     types::{ComponentType, Network},
 };
@@ -79,6 +79,7 @@ impl EventIndexerComponentManifest {
                 interval_sec: interval,
                 delay_sec: None,
                 is_round_start_timing: None,
+                schedule: None,
             },
             cycles: None,
         }
@@ -96,10 +97,23 @@ impl EventIndexerCodeGenerator {
 impl CodeGenerator for EventIndexerCodeGenerator {
     fn generate_code(
         &self,
-        _interface_contract: Option<ethabi::Contract>,
+        interface_contract: Option<ethabi::Contract>,
+    ) -> anyhow::Result<GeneratedCodes> {
+        let interfaces = self
+            .manifest
+            .required_interface()
+            .zip(interface_contract)
+            .into_iter()
+            .collect();
+        self.generate_code_with_interfaces(&interfaces)
+    }
+
+    fn generate_code_with_interfaces(
+        &self,
+        interfaces: &BTreeMap<String, ethabi::Contract>,
     ) -> anyhow::Result<GeneratedCodes> {
         Ok(GeneratedCodes {
-            lib: canisters::event_indexer::generate_codes(&self.manifest)?,
+            lib: canisters::event_indexer::generate_codes(&self.manifest, interfaces)?,
             types: None,
         })
     }
@@ -137,6 +151,23 @@ impl ComponentManifest for EventIndexerComponentManifest {
         canisters::event_indexer::validate_manifest(self)
     }
 
+    fn rpc_endpoints(&self) -> Vec<RpcEndpoint> {
+        let datasource = &self.datasource;
+        vec![RpcEndpoint::new(
+            "datasource.network.rpc_url",
+            &datasource.network.rpc_url,
+            datasource.network.chain_id,
+            vec![datasource.id.clone()],
+        )]
+    }
+
+    fn validate_with_interfaces(
+        &self,
+        interfaces: &BTreeMap<String, ethabi::Contract>,
+    ) -> anyhow::Result<()> {
+        canisters::event_indexer::validate_with_interfaces(self, interfaces)
+    }
+
     fn component_type(&self) -> ComponentType {
         ComponentType::EventIndexer
     }
@@ -328,7 +359,7 @@ timer_settings:
                     id: "0x6B175474E89094C44Da98b954EedeAC495271d0F".to_string(),
                     event: EventIndexerEventDefinition {
                         identifier: "Transfer".to_string(),
-                        interface: Some("ERC20.json".to_string())
+                        interface: Some("ERC20.json".to_string()),
                     },
                     network: SourceNetwork {
                         rpc_url: "https://eth.llamarpc.com".to_string(),
@@ -342,6 +373,7 @@ timer_settings:
                     interval_sec: 3600,
                     delay_sec: None,
                     is_round_start_timing: None,
+                    schedule: None,
                 },
                 cycles: None,
             }
@@ -389,6 +421,7 @@ timer_settings:
                 interval_sec: 3600,
                 delay_sec: None,
                 is_round_start_timing: None,
+                schedule: None,
             },
             cycles: None,
         };
@@ -408,4 +441,41 @@ timer_settings:
             .generate_user_impl_template()
             .is_err());
     }
+
+    #[test]
+    fn test_batch_size() {
+        let yaml = r#"
+version: v1
+metadata:
+    label: sample_event_indexer
+    type: event_indexer
+    description: Description
+datasource:
+    id: 0x6B175474E89094C44Da98b954EedeAC495271d0F
+    event:
+        identifier: Transfer
+        interface: ERC20.json
+    network:
+        rpc_url: https://eth.llamarpc.com
+        chain_id: 1
+    from: 17660942
+    batch_size: 500
+timer_settings:
+    interval_sec: 3600
+        "#;
+        let schema = serde_json::from_str(include_str!(
+            "../../../../resources/schema/event_indexer.json"
+        ))
+        .expect("Invalid json");
+        let instance = serde_yaml::from_str(yaml).expect("Invalid yaml");
+        let compiled = JSONSchema::compile(&schema).expect("Invalid schema");
+        assert!(compiled.validate(&instance).is_ok());
+
+        let manifest = serde_yaml::from_str::<EventIndexerComponentManifest>(yaml).unwrap();
+        assert!(manifest.validate_manifest().is_ok());
+
+        let mut invalid = manifest;
+        invalid.datasource.batch_size = Some(0);
+        assert!(invalid.validate_manifest().is_err());
+    }
 }
@@ -11,10 +11,9 @@ use serde_json::json;
 use crate::{
     lib::{
         codegen::{
-            canisters, components::common::custom_tags_interval_sec, oracle::get_oracle_address,
-            scripts,
+            canisters, chains::ChainRegistry, components::common::custom_tags_interval_sec, scripts,
         },
-        utils::component_ids_manager::ComponentIdsManager,
+        utils::{component_ids_manager::ComponentIdsManager, rpc::RpcEndpoint},
     },
     types::{ComponentType, Network},
 };
@@ -39,11 +38,14 @@ pub struct RelayerComponentManifest {
     pub version: String,
     pub metadata: ComponentMetadata,
     pub datasource: DatasourceForCanister,
-    pub destination: DestinationField, // TODO: multiple destinations
+    pub destination: DestinationField,
     pub timer_settings: TimerSettings,
     pub conversion_parameter: Option<RelayerConversionParameter>,
     pub lens_targets: Option<LensTargets>,
     pub cycles: Option<CycleManagementsManifest>,
+    /// Chain registry of the project, which omitted fields of the destination are taken from
+    #[serde(skip)]
+    pub chain_registry: Option<ChainRegistry>,
 }
 
 impl RelayerComponentManifest {
@@ -73,36 +75,27 @@ impl RelayerComponentManifest {
                 interval_sec: interval,
                 delay_sec: None,
                 is_round_start_timing: None,
+                schedule: None,
             },
             cycles: None,
+            chain_registry: None,
         }
     }
 
-    fn abi_file_path(&self) -> String {
-        match self.destination_type() {
-            Some(DestinationType::Custom) => {
-                format!(
-                    "__interfaces/{}",
-                    self.destination
-                        .interface
-                        .clone()
-                        .expect("missing field: interface is required for custom oracle")
-                )
-            }
-            Some(_) => "__interfaces/Oracle.json".to_string(),
-            _ => panic!("missing field: destination_type is required"),
-        }
+    /// Load the chain registry of the project, extended by chains.yaml in the project root
+    pub fn with_project_root(self, project_root: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            chain_registry: Some(ChainRegistry::load(project_root)?),
+            ..self
+        })
     }
 
-    fn relay_method_name(&self) -> String {
-        match self.destination_type() {
-            Some(DestinationType::Custom) => self
-                .destination
-                .method_name
-                .clone()
-                .expect("missing field: method_name is required for custom oracle"),
-            Some(_) => "update_state".to_string(),
-            _ => panic!("missing field: destination_type is required"),
+    /// Destination with the omitted oracle address and RPC URL filled in from the chain registry,
+    /// which is the bundled one unless loaded from the project
+    pub fn resolved_destination(&self) -> DestinationField {
+        match &self.chain_registry {
+            Some(registry) => self.destination.clone().resolve(registry),
+            None => self.destination.clone().resolve(&ChainRegistry::bundled()),
         }
     }
 }
@@ -117,9 +110,9 @@ impl From<RelayerComponentManifest> for chainsight_cdk::config::components::Rela
                     ref location,
                     ..
                 },
-            ref destination,
             ..
         } = val;
+        let destination = val.resolved_destination();
 
         let lens_parameter = if val.lens_targets.is_some() {
             let interface = if method.interface.is_some() {
@@ -141,8 +134,8 @@ impl From<RelayerComponentManifest> for chainsight_cdk::config::components::Rela
             },
             method_identifier: method.identifier.clone(),
             destination: destination.oracle_address.clone(),
-            abi_file_path: val.abi_file_path(),
-            method_name: val.relay_method_name(),
+            abi_file_path: destination.abi_file_path(),
+            method_name: destination.relay_method_name(),
             lens_parameter,
             conversion_parameter: val.conversion_parameter,
         }
@@ -224,6 +217,16 @@ impl ComponentManifest for RelayerComponentManifest {
         canisters::relayer::validate_manifest(self)
     }
 
+    fn rpc_endpoints(&self) -> Vec<RpcEndpoint> {
+        let destination = self.resolved_destination();
+        vec![RpcEndpoint::new(
+            "destination.rpc_url",
+            &destination.rpc_url,
+            destination.network_id as u64,
+            vec![destination.oracle_address.clone()],
+        )]
+    }
+
     fn component_type(&self) -> ComponentType {
         ComponentType::Relayer
     }
@@ -271,11 +274,12 @@ impl ComponentManifest for RelayerComponentManifest {
             attributes: Attributes,
         }
         let mut res = HashMap::new();
+        let destination = self.resolved_destination();
         let dest = Destination {
             destination_type: "evm".to_string(),
-            destination: self.destination.oracle_address.clone(),
+            destination: destination.oracle_address,
             attributes: Attributes {
-                chain_id: self.destination.network_id,
+                chain_id: destination.network_id,
             },
         };
         res.insert(
@@ -333,10 +337,16 @@ pub struct DestinationField {
     pub network_id: u32,
     #[serde(rename = "type")]
     pub type_: DestinationType,
+    /// Taken from the chain registry if omitted
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub oracle_address: String,
+    /// Taken from the chain registry if omitted
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub rpc_url: String,
     pub method_name: Option<String>,
     pub interface: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionSettings>,
 }
 
 impl DestinationField {
@@ -353,19 +363,98 @@ impl DestinationField {
             rpc_url,
             method_name: None,
             interface: None,
+            transaction: None,
         }
     }
+
+    /// Destination to the oracle of the chain in the registry
+    pub fn from_registry(
+        registry: &ChainRegistry,
+        network_id: u32,
+        destination_type: DestinationType,
+    ) -> Self {
+        Self::new(network_id, destination_type, String::new(), String::new()).resolve(registry)
+    }
+
+    /// Fill in the omitted oracle address and RPC URL from the chain registry
+    pub fn resolve(mut self, registry: &ChainRegistry) -> Self {
+        if self.oracle_address.is_empty() {
+            if let Some(address) = registry.oracle_address(self.network_id, self.type_) {
+                self.oracle_address = address;
+            }
+        }
+        if self.rpc_url.is_empty() {
+            if let Some(url) = registry.default_rpc_url(self.network_id) {
+                self.rpc_url = url;
+            }
+        }
+        self
+    }
 }
-impl Default for DestinationField {
-    fn default() -> Self {
-        let network_id = 80001; // NOTE: (temp) polygon mumbai
-        let oracle_type = DestinationType::Uint256;
-        Self::new(
-            network_id,
-            oracle_type,
-            get_oracle_address(network_id),
-            "https://eth.llamarpc.com".to_string(),
-        )
+/// Settings of the transactions to relay
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct TransactionSettings {
+    /// Threshold ECDSA key to sign the transactions, depending on the network if not specified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ecdsa_key_name: Option<EcdsaKeyName>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum EcdsaKeyName {
+    #[serde(rename = "dfx_test_key")]
+    DfxTestKey,
+    #[serde(rename = "test_key_1")]
+    TestKey1,
+    #[serde(rename = "key_1")]
+    Key1,
+}
+
+impl EcdsaKeyName {
+    /// Environment of the canister to sign with the key
+    pub fn to_sdk_env(&self) -> chainsight_cdk::core::Env {
+        match self {
+            EcdsaKeyName::DfxTestKey => chainsight_cdk::core::Env::LocalDevelopment,
+            EcdsaKeyName::TestKey1 => chainsight_cdk::core::Env::Test,
+            EcdsaKeyName::Key1 => chainsight_cdk::core::Env::Production,
+        }
+    }
+
+    pub fn is_available_on(&self, network: &Network) -> bool {
+        match network {
+            Network::Local => *self == EcdsaKeyName::DfxTestKey,
+            Network::IC => *self != EcdsaKeyName::DfxTestKey,
+        }
+    }
+}
+
+impl DestinationField {
+    /// Threshold ECDSA key specified in the transaction settings
+    pub fn ecdsa_key_name(&self) -> Option<EcdsaKeyName> {
+        self.transaction.as_ref().and_then(|t| t.ecdsa_key_name)
+    }
+
+    pub fn abi_file_path(&self) -> String {
+        match self.type_ {
+            DestinationType::Custom => {
+                format!(
+                    "__interfaces/{}",
+                    self.interface
+                        .clone()
+                        .expect("missing field: interface is required for custom oracle")
+                )
+            }
+            _ => "__interfaces/Oracle.json".to_string(),
+        }
+    }
+
+    pub fn relay_method_name(&self) -> String {
+        match self.type_ {
+            DestinationType::Custom => self
+                .method_name
+                .clone()
+                .expect("missing field: method_name is required for custom oracle"),
+            _ => "update_state".to_string(),
+        }
     }
 }
 
@@ -375,8 +464,11 @@ mod tests {
     use jsonschema::JSONSchema;
 
     use crate::lib::{
-        codegen::components::common::{
-            DatasourceForCanister, DatasourceLocationForCanister, DatasourceMethod,
+        codegen::{
+            chains::CHAINS_FILENAME,
+            components::common::{
+                DatasourceForCanister, DatasourceLocationForCanister, DatasourceMethod,
+            },
         },
         test_utils::SrcString,
     };
@@ -412,6 +504,7 @@ mod tests {
                 rpc_url: "https://eth.llamarpc.com".to_string(),
                 method_name: None,
                 interface: None,
+                transaction: None,
             },
             lens_targets: None,
             conversion_parameter: None,
@@ -419,8 +512,10 @@ mod tests {
                 interval_sec: 3600,
                 delay_sec: None,
                 is_round_start_timing: None,
+                schedule: None,
             },
             cycles: None,
+            chain_registry: None,
         }
     }
 
@@ -484,6 +579,7 @@ timer_settings:
                     rpc_url: "https://eth.llamarpc.com".to_string(),
                     method_name: None,
                     interface: None,
+                    transaction: None,
                 },
                 lens_targets: None,
                 conversion_parameter: None,
@@ -491,8 +587,10 @@ timer_settings:
                     interval_sec: 3600,
                     delay_sec: None,
                     is_round_start_timing: None,
+                    schedule: None,
                 },
                 cycles: None,
+                chain_registry: None,
             }
         );
         let schema =
@@ -561,6 +659,99 @@ timer_settings:
             generated_user_impl_template.types.unwrap()
         );
     }
+    #[test]
+    fn test_transaction_settings() {
+        let yaml = r#"
+version: v1
+metadata:
+    label: sample_relayer
+    type: relayer
+    description: Description
+datasource:
+    location:
+        id: datasource_canister_id
+    method:
+        identifier: 'get_last_snapshot : () -> (record { value : text; timestamp : nat64 })'
+        args: []
+destination:
+    network_id: 10
+    type: uint256
+    oracle_address: 0x0539a0EF8e5E60891fFf0958A059E049e43020d9
+    rpc_url: https://mainnet.optimism.io
+    transaction:
+        ecdsa_key_name: test_key_1
+timer_settings:
+    interval_sec: 3600
+        "#;
+        let schema =
+            serde_json::from_str(include_str!("../../../../resources/schema/relayer.json"))
+                .expect("Invalid json");
+        let instance = serde_yaml::from_str(yaml).expect("Invalid yaml");
+        let compiled = JSONSchema::compile(&schema).expect("Invalid schema");
+        assert!(compiled.validate(&instance).is_ok());
+
+        let mut manifest = serde_yaml::from_str::<RelayerComponentManifest>(yaml).unwrap();
+        manifest.id = Some("sample_relayer".to_string());
+        assert_eq!(
+            manifest.destination.transaction,
+            Some(TransactionSettings {
+                ecdsa_key_name: Some(EcdsaKeyName::TestKey1),
+            })
+        );
+        assert!(!canisters::relayer::generate_codes(&manifest)
+            .unwrap()
+            .contains("ecdsa_key_name"));
+
+        let key_name = manifest.destination.ecdsa_key_name().unwrap();
+        assert!(key_name.is_available_on(&Network::IC));
+        assert!(!key_name.is_available_on(&Network::Local));
+        assert!(EcdsaKeyName::DfxTestKey.is_available_on(&Network::Local));
+        assert!(!EcdsaKeyName::DfxTestKey.is_available_on(&Network::IC));
+    }
+
+    #[test]
+    fn test_resolved_destination_with_project_root() {
+        let project_root =
+            std::env::temp_dir().join(format!("csx_test_relayer_chains_{}", std::process::id()));
+        std::fs::create_dir_all(&project_root).unwrap();
+        let project_root_str = project_root.to_str().unwrap();
+        let mut manifest = sample_relayer_manifest();
+        manifest.destination = DestinationField::new(
+            31337,
+            DestinationType::Uint256,
+            String::new(),
+            String::new(),
+        );
+
+        std::fs::write(
+            project_root.join(CHAINS_FILENAME),
+            r#"- chain_id: 31337
+  name: Anvil
+  rpc_urls:
+  - http://localhost:8545
+  oracles:
+    uint256: 0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512
+"#,
+        )
+        .unwrap();
+        let destination = manifest
+            .clone()
+            .with_project_root(project_root_str)
+            .unwrap()
+            .resolved_destination();
+        assert_eq!(destination.rpc_url, "http://localhost:8545");
+        assert_eq!(
+            destination.oracle_address,
+            "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512"
+        );
+        assert!(manifest.resolved_destination().rpc_url.is_empty());
+
+        std::fs::write(project_root.join(CHAINS_FILENAME), "- chain_id: invalid").unwrap();
+        let loaded = manifest.with_project_root(project_root_str);
+        std::fs::remove_dir_all(&project_root).unwrap();
+        assert!(loaded.is_err());
+    }
+
     #[test]
     fn test_oracle_type() {
         assert_eq!(oracle_type(Some(DestinationType::Uint256)), "uint256");
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use anyhow::Ok;
 use chainsight_cdk::{config::components::CommonConfig, initializer::CycleManagements};
@@ -8,7 +8,7 @@ use serde_json::json;
 use crate::{
     lib::{
         codegen::{canisters, components::common::SourceType, scripts},
-        utils::component_ids_manager::ComponentIdsManager,
+        utils::{component_ids_manager::ComponentIdsManager, rpc::RpcEndpoint},
     },
     types::{ComponentType, Network},
 };
@@ -142,6 +142,7 @@ impl SnapshotIndexerEVMComponentManifest {
                 interval_sec: interval,
                 delay_sec: None,
                 is_round_start_timing: None,
+                schedule: None,
             },
             cycles: None,
         }
@@ -185,6 +186,23 @@ impl ComponentManifest for SnapshotIndexerEVMComponentManifest {
         canisters::snapshot_indexer_evm::validate_manifest(self)
     }
 
+    fn rpc_endpoints(&self) -> Vec<RpcEndpoint> {
+        let location = &self.datasource.location;
+        vec![RpcEndpoint::new(
+            "datasource.location.args.rpc_url",
+            &location.args.rpc_url,
+            location.args.network_id as u64,
+            vec![location.id.clone()],
+        )]
+    }
+
+    fn validate_with_interfaces(
+        &self,
+        interfaces: &BTreeMap<String, ethabi::Contract>,
+    ) -> anyhow::Result<()> {
+        canisters::snapshot_indexer_evm::validate_with_interfaces(self, interfaces)
+    }
+
     fn component_type(&self) -> ComponentType {
         ComponentType::SnapshotIndexerEVM
     }
@@ -238,7 +256,7 @@ impl ComponentManifest for SnapshotIndexerEVMComponentManifest {
     }
 }
 
-fn yaml_to_json(yaml_value: serde_yaml::Value) -> serde_json::Value {
+pub(crate) fn yaml_to_json(yaml_value: serde_yaml::Value) -> serde_json::Value {
     // Convert serde_yaml::Value to a JSON string
     let json_str = serde_json::to_string(&yaml_value).unwrap();
 
@@ -308,12 +326,13 @@ timer_settings:
                         identifier: "totalSupply():(uint256)".to_owned(),
                         interface: Some("ERC20.json".to_string()),
                         args: vec![]
-                    }
+                    },
                 },
                 timer_settings: TimerSettings {
                     interval_sec: 3600,
                     delay_sec: None,
                     is_round_start_timing: None,
+                    schedule: None,
                 },
                 cycles: None,
             }
@@ -358,6 +377,7 @@ timer_settings:
                 interval_sec: 3600,
                 delay_sec: None,
                 is_round_start_timing: None,
+                schedule: None,
             },
             cycles: None,
         };
@@ -1,5 +1,9 @@
-use std::collections::{BTreeMap, HashMap};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
 
+use anyhow::ensure;
 use chainsight_cdk::{
     config::components::{CommonConfig, SnapshotIndexerHTTPSConfigQueries},
     initializer::CycleManagements,
@@ -10,11 +14,11 @@ use crate::{
     lib::{
         codegen::{
             canisters::snapshot_indexer_https::{
-                generate_app, generate_codes, JsonTypeGenStrategy,
+                generate_app, generate_codes, load_sample_json, JsonTypeGenStrategy,
             },
             components::common::SourceType,
         },
-        utils::{component_ids_manager::ComponentIdsManager, url::is_supporting_ipv6_url},
+        utils::component_ids_manager::ComponentIdsManager,
     },
     types::{ComponentType, Network},
 };
@@ -32,6 +36,19 @@ pub struct SnapshotIndexerHTTPSDataSource {
     pub url: String,
     pub headers: BTreeMap<String, String>,
     pub queries: SnapshotIndexerHTTPSDataSourceQueries,
+    /// method, body, select and max_response_bytes are rejected by the validation,
+    /// as the current chainsight-cdk does not support them yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<HttpMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<SnapshotIndexerHTTPSDataSourceBody>,
+    /// JSON path to the part of the response to be stored (e.g. `$.data.pools[0]`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub select: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_response_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_sample: Option<SnapshotIndexerHTTPSResponseSample>,
 }
 impl Default for SnapshotIndexerHTTPSDataSource {
     fn default() -> Self {
@@ -42,10 +59,48 @@ impl Default for SnapshotIndexerHTTPSDataSource {
                 ("ids".to_string(), "dai".to_string()),
                 ("vs_currencies".to_string(), "usd".to_string()),
             ])),
+            method: None,
+            body: None,
+            select: None,
+            max_response_bytes: None,
+            response_sample: None,
         }
     }
 }
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", content = "value")]
+pub enum SnapshotIndexerHTTPSDataSourceBody {
+    #[serde(rename = "static")]
+    Static(serde_json::Value),
+    #[serde(rename = "dynamic")]
+    Dynamic,
+}
+/// Local document used to generate the response types instead of fetching the url
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct SnapshotIndexerHTTPSResponseSample {
+    pub path: String,
+    #[serde(default)]
+    pub format: ResponseSampleFormat,
+    /// JSON pointer to the response schema in the document (e.g. `/components/schemas/Price`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pointer: Option<String>,
+}
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseSampleFormat {
+    #[default]
+    Json,
+    JsonSchema,
+    #[serde(rename = "openapi")]
+    OpenApi,
+}
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(tag = "type", content = "value")]
 pub enum SnapshotIndexerHTTPSDataSourceQueries {
     #[serde(rename = "static")]
@@ -78,6 +133,9 @@ pub struct SnapshotIndexerHTTPSComponentManifest {
     pub datasource: SnapshotIndexerHTTPSDataSource,
     pub timer_settings: TimerSettings,
     pub cycles: Option<CycleManagementsManifest>,
+    /// Root of the project, which local documents in the manifest are relative to
+    #[serde(skip)]
+    pub project_root: Option<String>,
 }
 
 impl SnapshotIndexerHTTPSComponentManifest {
@@ -109,10 +167,23 @@ impl SnapshotIndexerHTTPSComponentManifest {
                 interval_sec: interval,
                 delay_sec: None,
                 is_round_start_timing: None,
+                schedule: None,
             },
             cycles: None,
+            project_root: None,
+        }
+    }
+
+    pub fn with_project_root(self, project_root: &str) -> Self {
+        Self {
+            project_root: Some(project_root.to_owned()),
+            ..self
         }
     }
+
+    pub fn project_root(&self) -> &Path {
+        Path::new(self.project_root.as_deref().unwrap_or("."))
+    }
 }
 impl From<SnapshotIndexerHTTPSComponentManifest>
     for chainsight_cdk::config::components::SnapshotIndexerHTTPSConfig
@@ -187,8 +258,35 @@ impl ComponentManifest for SnapshotIndexerHTTPSComponentManifest {
         Ok(self.yaml_str_with_configs(yaml, "snapshot_indexer_https".to_string()))
     }
 
+    fn outcall_urls(&self) -> Vec<(String, String)> {
+        vec![("datasource.url".to_string(), self.datasource.url.clone())]
+    }
+
     fn validate_manifest(&self) -> anyhow::Result<()> {
-        // is_supporting_ipv6_url(&self.datasource.url)?;
+        let datasource = &self.datasource;
+        // NOTE: SnapshotIndexerHTTPSConfig has no fields for them, so the canister would ignore them
+        for (name, specified) in [
+            ("method", datasource.method.is_some()),
+            ("body", datasource.body.is_some()),
+            ("select", datasource.select.is_some()),
+            (
+                "max_response_bytes",
+                datasource.max_response_bytes.is_some(),
+            ),
+        ] {
+            ensure!(
+                !specified,
+                "datasource.{} is not supported by the current chainsight-cdk yet",
+                name
+            );
+        }
+        if let Some(sample) = &datasource.response_sample {
+            ensure!(
+                sample.format != ResponseSampleFormat::OpenApi || sample.pointer.is_some(),
+                "datasource.response_sample.pointer is required for openapi documents"
+            );
+            load_sample_json(sample, self.project_root())?;
+        }
         Ok(())
     }
 
@@ -247,7 +345,7 @@ mod tests {
         fn generate_code(
             &self,
             struct_name: &str,
-            _url: &str,
+            _input: &str,
             _options: json_typegen_shared::Options,
         ) -> anyhow::Result<String> {
             let struct_str = format!(
@@ -263,6 +361,14 @@ mod tests {
             );
             Ok(struct_str)
         }
+
+        fn fetch_sample(
+            &self,
+            _url: &str,
+            _headers: &BTreeMap<String, String>,
+        ) -> anyhow::Result<serde_json::Value> {
+            Ok(serde_json::json!({ "data": { "id": "dai", "vs_currencies": "usd", "dai": "1" } }))
+        }
     }
 
     use super::*;
@@ -336,13 +442,20 @@ timer_settings:
                         ("ids".to_string(), "dai".to_string()),
                         ("vs_currencies".to_string(), "usd".to_string()),
                     ])),
+                    method: None,
+                    body: None,
+                    select: None,
+                    max_response_bytes: None,
+                    response_sample: None,
                 },
                 timer_settings: TimerSettings {
                     interval_sec: 3600,
                     delay_sec: None,
                     is_round_start_timing: None,
+                    schedule: None,
                 },
                 cycles: None,
+                project_root: None,
             }
         );
     }
@@ -415,13 +528,20 @@ timer_settings:
                         .into_iter()
                         .collect(),
                     queries: SnapshotIndexerHTTPSDataSourceQueries::Dynamic,
+                    method: None,
+                    body: None,
+                    select: None,
+                    max_response_bytes: None,
+                    response_sample: None,
                 },
                 timer_settings: TimerSettings {
                     interval_sec: 3600,
                     delay_sec: None,
                     is_round_start_timing: None,
+                    schedule: None,
                 },
                 cycles: None,
+                project_root: None,
             }
         );
     }
@@ -438,6 +558,71 @@ timer_settings:
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_to_manifest_struct_response_sample() {
+        let yaml = r#"
+version: v1
+metadata:
+    label: sample_snapshot_indexer_https
+    type: snapshot_indexer_https
+    description: Description
+datasource:
+    url: https://api.coingecko.com/api/v3/simple/price
+    headers:
+        content-type: application/json
+    queries:
+        type: dynamic
+    response_sample:
+        path: interfaces/coingecko.yaml
+        format: openapi
+        pointer: /components/schemas/Price
+timer_settings:
+    interval_sec: 3600
+        "#;
+        let component =
+            serde_yaml::from_str::<SnapshotIndexerHTTPSComponentManifest>(yaml).unwrap();
+        assert_eq!(
+            component.datasource.response_sample,
+            Some(SnapshotIndexerHTTPSResponseSample {
+                path: "interfaces/coingecko.yaml".to_string(),
+                format: ResponseSampleFormat::OpenApi,
+                pointer: Some("/components/schemas/Price".to_string()),
+            })
+        );
+
+        let schema = serde_json::from_str(include_str!(
+            "../../../../resources/schema/snapshot_indexer_https.json"
+        ))
+        .expect("Invalid json");
+        let instance = serde_yaml::from_str(yaml).expect("Invalid yaml");
+        let compiled = JSONSchema::compile(&schema).expect("Invalid schema");
+        assert!(compiled.validate(&instance).is_ok());
+    }
+
+    #[test]
+    fn test_validate_request_options() {
+        let mut manifest = serde_yaml::from_str::<SnapshotIndexerHTTPSComponentManifest>(
+            MANIFEST_YAML_STATIC_QUERIES,
+        )
+        .unwrap();
+        assert!(manifest.validate_manifest().is_ok());
+
+        manifest.datasource.method = Some(HttpMethod::Post);
+        manifest.datasource.body = Some(SnapshotIndexerHTTPSDataSourceBody::Static(
+            serde_json::json!({ "query": "{ pools { id } }" }),
+        ));
+        assert!(manifest.validate_manifest().is_err());
+
+        manifest.datasource.method = None;
+        manifest.datasource.body = None;
+        manifest.datasource.select = Some("$.data".to_string());
+        assert!(manifest.validate_manifest().is_err());
+
+        manifest.datasource.select = None;
+        manifest.datasource.max_response_bytes = Some(10_000);
+        assert!(manifest.validate_manifest().is_err());
+    }
+
     #[test]
     fn test_snapshot_outputs_dynamic_queries() {
         let mut manifest = serde_yaml::from_str::<SnapshotIndexerHTTPSComponentManifest>(
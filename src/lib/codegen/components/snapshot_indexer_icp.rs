@@ -68,6 +68,7 @@ impl SnapshotIndexerICPComponentManifest {
                 interval_sec: interval,
                 delay_sec: None,
                 is_round_start_timing: None,
+                schedule: None,
             },
             is_target_component: None,
             lens_targets: None,
@@ -327,6 +328,7 @@ timer_settings:
                     interval_sec: 3600,
                     delay_sec: None,
                     is_round_start_timing: None,
+                    schedule: None,
                 },
                 cycles: None,
             }
@@ -370,6 +372,7 @@ timer_settings:
                 interval_sec: 3600,
                 delay_sec: None,
                 is_round_start_timing: None,
+                schedule: None,
             },
             cycles: None,
         };
@@ -1,11 +1,31 @@
 use std::path::Path;
 
+use anyhow::{bail, ensure, Context};
+use candid::{
+    types::{
+        value::{IDLField, IDLValue, VariantValue},
+        Field, Function, Label, Type, TypeInner,
+    },
+    IDLArgs, Int, Nat, Principal, TypeEnv,
+};
+use candid_parser::{check_prog, IDLProg};
 use chainsight_cdk::{
     config::components::LENS_FUNCTION_ARGS_TYPE,
     convert::candid::{read_did_to_string_without_service, CanisterMethodIdentifier},
 };
 
-use crate::lib::utils::paths::canister_did_path_str;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+use crate::lib::{
+    codegen::project::ProjectManifestData,
+    utils::{
+        paths::{bindings_name, canister_did_path_str},
+        PROJECT_MANIFEST_FILENAME,
+    },
+};
+
+use super::{algorithm_lens::AlgorithmLensComponentManifest, common::DatasourceMethod};
 
 // Generate types.rs code using the type information in bindings
 pub fn generate_types_from_bindings(id: &str, identifier: &str) -> anyhow::Result<String> {
@@ -41,6 +61,17 @@ pub fn get_did_by_component_id(component_id: &str) -> Option<String> {
     }
 }
 
+// Get manifest path of the component in the same project.
+pub fn get_manifest_path_by_component_id(component_id: &str) -> Option<String> {
+    let project = ProjectManifestData::load(PROJECT_MANIFEST_FILENAME).ok()?;
+    project
+        .components
+        .into_iter()
+        .map(|c| c.component_path)
+        .find(|path| Path::new(path).file_stem().and_then(|s| s.to_str()) == Some(component_id))
+        .filter(|path| Path::new(path).is_file())
+}
+
 pub fn generate_method_identifier(
     identifier: &str,
     interface: &Option<String>,
@@ -53,6 +84,531 @@ pub fn generate_method_identifier(
     }
 }
 
+/// Candid function type of a method identifier, type checked together with the .did it refers to
+pub struct CandidMethodType {
+    pub env: TypeEnv,
+    pub name: String,
+    pub func: Function,
+}
+
+impl CandidMethodType {
+    /// Fields of the response if it is a single record (type aliases are traced)
+    pub fn response_record_fields(&self) -> Vec<Field> {
+        if self.func.rets.len() != 1 {
+            return vec![];
+        }
+        match self.env.trace_type(&self.func.rets[0]) {
+            Ok(ty) => match ty.as_ref() {
+                TypeInner::Record(fields) => fields.clone(),
+                _ => vec![],
+            },
+            Err(_) => vec![],
+        }
+    }
+}
+
+impl CandidMethodType {
+    /// Candid-encode args in the manifest with the argument types of the method
+    pub fn encode_args(&self, args: &[serde_yaml::Value]) -> anyhow::Result<Vec<u8>> {
+        ensure!(
+            args.len() == self.func.args.len(),
+            "{} takes {} args, but {} given",
+            self.name,
+            self.func.args.len(),
+            args.len()
+        );
+        let values = self
+            .func
+            .args
+            .iter()
+            .zip(args)
+            .enumerate()
+            .map(|(i, (ty, value))| {
+                yaml_to_idl_value(&self.env, ty, value, &format!("args[{}]", i))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        IDLArgs::new(&values)
+            .to_bytes_with_types(&self.env, &self.func.args)
+            .context("failed to encode args")
+    }
+}
+
+// Convert a yaml value to the candid value of the type, `path` is used in error messages
+fn yaml_to_idl_value(
+    env: &TypeEnv,
+    ty: &Type,
+    value: &serde_yaml::Value,
+    path: &str,
+) -> anyhow::Result<IDLValue> {
+    use serde_yaml::Value as Yaml;
+    let ty = env.trace_type(ty)?;
+    let value = match (ty.as_ref(), value) {
+        (TypeInner::Null, Yaml::Null) => IDLValue::Null,
+        (TypeInner::Reserved, _) => IDLValue::Reserved,
+        (TypeInner::Bool, Yaml::Bool(v)) => IDLValue::Bool(*v),
+        (TypeInner::Text, Yaml::String(v)) => IDLValue::Text(v.clone()),
+        (TypeInner::Principal, Yaml::String(v)) => IDLValue::Principal(
+            Principal::from_text(v)
+                .with_context(|| format!("{}: invalid principal {}", path, v))?,
+        ),
+        (TypeInner::Nat, _) => IDLValue::Nat(
+            integer_str(value, path)?
+                .parse::<Nat>()
+                .with_context(|| format!("{}: {:?} is not a nat", path, value))?,
+        ),
+        (TypeInner::Int, _) => IDLValue::Int(
+            integer_str(value, path)?
+                .parse::<Int>()
+                .with_context(|| format!("{}: {:?} is not an int", path, value))?,
+        ),
+        (TypeInner::Nat8, _) => IDLValue::Nat8(parse_integer(value, "nat8", path)?),
+        (TypeInner::Nat16, _) => IDLValue::Nat16(parse_integer(value, "nat16", path)?),
+        (TypeInner::Nat32, _) => IDLValue::Nat32(parse_integer(value, "nat32", path)?),
+        (TypeInner::Nat64, _) => IDLValue::Nat64(parse_integer(value, "nat64", path)?),
+        (TypeInner::Int8, _) => IDLValue::Int8(parse_integer(value, "int8", path)?),
+        (TypeInner::Int16, _) => IDLValue::Int16(parse_integer(value, "int16", path)?),
+        (TypeInner::Int32, _) => IDLValue::Int32(parse_integer(value, "int32", path)?),
+        (TypeInner::Int64, _) => IDLValue::Int64(parse_integer(value, "int64", path)?),
+        (TypeInner::Float32, Yaml::Number(n)) => IDLValue::Float32(n.as_f64().unwrap() as f32),
+        (TypeInner::Float64, Yaml::Number(n)) => IDLValue::Float64(n.as_f64().unwrap()),
+        (TypeInner::Opt(_), Yaml::Null) => IDLValue::None,
+        (TypeInner::Opt(inner), _) => {
+            IDLValue::Opt(Box::new(yaml_to_idl_value(env, inner, value, path)?))
+        }
+        // blob can be written as a 0x-prefixed hex string
+        (TypeInner::Vec(inner), Yaml::String(v))
+            if *env.trace_type(inner)?.as_ref() == TypeInner::Nat8 =>
+        {
+            let bytes = v
+                .strip_prefix("0x")
+                .and_then(|v| hex::decode(v).ok())
+                .with_context(|| format!("{}: invalid blob {}. use 0x-prefixed hex", path, v))?;
+            IDLValue::Blob(bytes)
+        }
+        (TypeInner::Vec(inner), Yaml::Sequence(values)) => IDLValue::Vec(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| yaml_to_idl_value(env, inner, v, &format!("{}[{}]", path, i)))
+                .collect::<anyhow::Result<Vec<_>>>()?,
+        ),
+        // tuple
+        (TypeInner::Record(fields), Yaml::Sequence(values))
+            if fields
+                .iter()
+                .all(|f| matches!(f.id.as_ref(), Label::Unnamed(_))) =>
+        {
+            ensure!(
+                values.len() == fields.len(),
+                "{}: tuple of {} elements expected, but {} given",
+                path,
+                fields.len(),
+                values.len()
+            );
+            let fields = fields
+                .iter()
+                .zip(values)
+                .enumerate()
+                .map(|(i, (f, v))| {
+                    Ok(IDLField {
+                        id: f.id.as_ref().clone(),
+                        val: yaml_to_idl_value(env, &f.ty, v, &format!("{}[{}]", path, i))?,
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            IDLValue::Record(fields)
+        }
+        (TypeInner::Record(fields), Yaml::Mapping(values)) => {
+            let mut keys = Vec::new();
+            for key in values.keys() {
+                let label = key
+                    .as_str()
+                    .map(label_of)
+                    .or_else(|| key.as_u64().map(|n| Label::Id(n as u32)))
+                    .with_context(|| format!("{}: invalid field name {:?}", path, key))?;
+                ensure!(
+                    fields.iter().any(|f| *f.id == label),
+                    "{}: unknown field {}",
+                    path,
+                    label
+                );
+                keys.push((label, key));
+            }
+            let mut idl_fields = vec![];
+            for field in fields {
+                let field_path = format!("{}.{}", path, field.id);
+                let value = keys
+                    .iter()
+                    .find(|(label, _)| *label == *field.id)
+                    .and_then(|(_, key)| values.get(*key));
+                let val = match value {
+                    Some(v) => yaml_to_idl_value(env, &field.ty, v, &field_path)?,
+                    None => match env.trace_type(&field.ty)?.as_ref() {
+                        TypeInner::Opt(_) => IDLValue::None,
+                        TypeInner::Null => IDLValue::Null,
+                        TypeInner::Reserved => IDLValue::Reserved,
+                        _ => bail!("{}: missing field", field_path),
+                    },
+                };
+                idl_fields.push(IDLField {
+                    id: field.id.as_ref().clone(),
+                    val,
+                });
+            }
+            IDLValue::Record(idl_fields)
+        }
+        // variant without value is written as the tag, others as a map of the tag to the value
+        (TypeInner::Variant(fields), Yaml::String(_))
+        | (TypeInner::Variant(fields), Yaml::Mapping(_)) => {
+            let (tag, inner) = match value {
+                Yaml::String(tag) => (tag.clone(), None),
+                Yaml::Mapping(m) if m.len() == 1 => {
+                    let (k, v) = m.iter().next().unwrap();
+                    let tag = k
+                        .as_str()
+                        .with_context(|| format!("{}: invalid tag {:?}", path, k))?;
+                    (tag.to_string(), Some(v))
+                }
+                _ => bail!("{}: variant must have exactly one tag", path),
+            };
+            let label = label_of(&tag);
+            let (index, field) = fields
+                .iter()
+                .enumerate()
+                .find(|(_, f)| *f.id == label)
+                .with_context(|| format!("{}: unknown tag {}", path, tag))?;
+            let field_path = format!("{}.{}", path, tag);
+            let val = match inner {
+                Some(v) => yaml_to_idl_value(env, &field.ty, v, &field_path)?,
+                None => yaml_to_idl_value(env, &field.ty, &Yaml::Null, &field_path)?,
+            };
+            IDLValue::Variant(VariantValue(
+                Box::new(IDLField {
+                    id: field.id.as_ref().clone(),
+                    val,
+                }),
+                index as u64,
+            ))
+        }
+        _ => bail!("{}: {:?} is not a value of {}", path, value, ty),
+    };
+    Ok(value)
+}
+
+fn label_of(name: &str) -> Label {
+    match name.parse::<u32>() {
+        Ok(n) => Label::Id(n),
+        Err(_) => Label::Named(name.to_string()),
+    }
+}
+
+// Integers out of the range of 64 bits are lost in yaml, so they can be passed as strings
+fn integer_str(value: &serde_yaml::Value, path: &str) -> anyhow::Result<String> {
+    match value {
+        serde_yaml::Value::Number(n) if n.is_u64() || n.is_i64() => Ok(n.to_string()),
+        serde_yaml::Value::String(v) => Ok(v.replace('_', "")),
+        _ => bail!(
+            "{}: {:?} is not an integer. pass big integers as strings",
+            path,
+            value
+        ),
+    }
+}
+
+fn parse_integer<T: std::str::FromStr>(
+    value: &serde_yaml::Value,
+    kind: &str,
+    path: &str,
+) -> anyhow::Result<T> {
+    let v = integer_str(value, path)?;
+    ensure!(
+        !v.is_empty()
+            && v.trim_start_matches('-')
+                .chars()
+                .all(|c| c.is_ascii_digit()),
+        "{}: {} is not an integer",
+        path,
+        v
+    );
+    v.parse::<T>()
+        .map_err(|_| anyhow::anyhow!("{}: {} is out of the range of {}", path, v, kind))
+}
+
+// Type-checked and candid-encoded args of the method to call a canister
+pub fn encode_canister_method_args(
+    location_id: &str,
+    method: &DatasourceMethod,
+) -> anyhow::Result<(CandidMethodType, Vec<u8>)> {
+    let interface = if method.interface.is_some() {
+        method.interface.clone()
+    } else {
+        get_did_by_component_id(location_id)
+    };
+    let method_type = candid_method_type(&method.identifier, &interface)?;
+    let bytes = method_type.encode_args(&method.args)?;
+    Ok((method_type, bytes))
+}
+
+// Candid-encoded CalculateArgs to call a lens, from the args in the manifest
+// NOTE: args for a lens with args are a single map of the fields of CalculateArgs
+pub fn encode_lens_calculate_args(
+    location_id: &str,
+    method: &DatasourceMethod,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let value = match method.args.as_slice() {
+        [] => return Ok(None),
+        [value] => value,
+        _ => bail!("args to call a lens must be a single map of the fields of CalculateArgs"),
+    };
+    let interface = if method.interface.is_some() {
+        method.interface.clone()
+    } else {
+        get_did_by_component_id(location_id)
+    };
+    let method_type = candid_method_type(&method.identifier, &interface)?;
+    let env = &method_type.env;
+    let lens_args = match method_type.func.args.as_slice() {
+        [ty] => env.trace_type(ty)?,
+        _ => bail!("{} is not a lens with args", method_type.name),
+    };
+    let calculate_args = match lens_args.as_ref() {
+        TypeInner::Record(fields) => fields
+            .iter()
+            .find(|f| *f.id == Label::Named("args".to_string()))
+            .map(|f| f.ty.clone()),
+        _ => None,
+    }
+    .with_context(|| format!("{} is not a lens with args", method_type.name))?;
+    let value = yaml_to_idl_value(env, &calculate_args, value, "args[0]")?;
+    let bytes = IDLArgs::new(&[value])
+        .to_bytes_with_types(env, &[calculate_args])
+        .context("failed to encode args")?;
+    Ok(Some(bytes))
+}
+
+// `call_args` returning the args in the manifest, decoded from the encoded bytes
+pub fn generate_call_args_fn(return_type: &Ident, arity: usize, bytes: &[u8]) -> TokenStream {
+    let decode = if arity == 1 {
+        quote! { candid::decode_one(CALL_ARGS).unwrap() }
+    } else {
+        quote! { candid::utils::decode_args(CALL_ARGS).unwrap() }
+    };
+    quote! {
+        // candid-encoded datasource.method.args in the manifest
+        const CALL_ARGS: &[u8] = &[#(#bytes),*];
+        pub fn call_args() -> #return_type {
+            #decode
+        }
+    }
+}
+
+// Types and `call_args` to call a lens with args, returning CalculateArgs in the manifest if any
+pub fn generate_lens_call_args(
+    id: &str,
+    location_id: &str,
+    method: &DatasourceMethod,
+) -> anyhow::Result<TokenStream> {
+    let bindings = format_ident!("{}", bindings_name(id));
+    let lens_args_ident = format_ident!("{}", LENS_FUNCTION_ARGS_TYPE);
+    let calculate_args_ident = format_ident!(
+        "{}",
+        AlgorithmLensComponentManifest::CALCULATE_ARGS_STRUCT_NAME
+    );
+    let call_args_fn = match encode_lens_calculate_args(location_id, method)? {
+        Some(bytes) => generate_call_args_fn(&calculate_args_ident, 1, &bytes),
+        None => quote! {
+            pub fn call_args() -> #calculate_args_ident {
+                todo!("generate CalculateArgs as args to call")
+            }
+        },
+    };
+    Ok(quote! {
+        pub type #calculate_args_ident = #bindings::#calculate_args_ident;
+        pub type #lens_args_ident = #bindings::#lens_args_ident;
+        #call_args_fn
+    })
+}
+
+// Parse a candid type like `opt vec nat64` without type definitions
+pub fn parse_candid_type(ty: &str) -> anyhow::Result<(TypeEnv, Type)> {
+    const NAME: &str = "T";
+    let prog: IDLProg = format!("type {} = {};", NAME, ty)
+        .parse()
+        .with_context(|| format!("invalid candid type: {}", ty))?;
+    let mut env = TypeEnv::new();
+    check_prog(&mut env, &prog).with_context(|| format!("invalid candid type: {}", ty))?;
+    let ty = env.find_type(NAME)?.clone();
+    Ok((env, ty))
+}
+
+// Rust type to hold a value of the candid type
+pub fn candid_type_to_rust(env: &TypeEnv, ty: &Type) -> anyhow::Result<TokenStream> {
+    let ty = env.trace_type(ty)?;
+    let rust_ty = match ty.as_ref() {
+        TypeInner::Bool => quote! { bool },
+        TypeInner::Nat => quote! { candid::Nat },
+        TypeInner::Int => quote! { candid::Int },
+        TypeInner::Nat8 => quote! { u8 },
+        TypeInner::Nat16 => quote! { u16 },
+        TypeInner::Nat32 => quote! { u32 },
+        TypeInner::Nat64 => quote! { u64 },
+        TypeInner::Int8 => quote! { i8 },
+        TypeInner::Int16 => quote! { i16 },
+        TypeInner::Int32 => quote! { i32 },
+        TypeInner::Int64 => quote! { i64 },
+        TypeInner::Float32 => quote! { f32 },
+        TypeInner::Float64 => quote! { f64 },
+        TypeInner::Text => quote! { String },
+        TypeInner::Principal => quote! { candid::Principal },
+        TypeInner::Opt(inner) => {
+            let inner = candid_type_to_rust(env, inner)?;
+            quote! { Option<#inner> }
+        }
+        TypeInner::Vec(inner) => {
+            let inner = candid_type_to_rust(env, inner)?;
+            quote! { Vec<#inner> }
+        }
+        _ => bail!("candid type {} is not supported", ty),
+    };
+    Ok(rust_ty)
+}
+
+pub fn candid_method_type(
+    identifier: &str,
+    interface: &Option<String>,
+) -> anyhow::Result<CandidMethodType> {
+    let did_str = if let Some(path) = interface {
+        read_did_to_string_without_service(path)?
+    } else {
+        String::new()
+    };
+    let prog_str = format!(
+        "{}\nservice : {{ {}; }}",
+        did_str,
+        identifier.trim().trim_end_matches(';')
+    );
+    let prog: IDLProg = prog_str
+        .parse()
+        .with_context(|| format!("Failed to parse method identifier: {}", identifier))?;
+    let mut env = TypeEnv::new();
+    let actor = check_prog(&mut env, &prog)
+        .with_context(|| format!("Failed to check method identifier: {}", identifier))?
+        .context("service is not defined")?;
+    let (name, func) = env
+        .as_service(&actor)?
+        .first()
+        .cloned()
+        .context("method is not defined")?;
+    let func = env.as_func(&func)?.clone();
+    Ok(CandidMethodType { env, name, func })
+}
+
+// Resolve the method name to the identifier with its signature, by the service in the .did
+// NOTE: named types are inlined so that the identifier does not depend on the .did
+pub fn method_identifier_from_did(name: &str, did_path: &str) -> anyhow::Result<String> {
+    let did_str = std::fs::read_to_string(did_path)
+        .with_context(|| format!("Failed to read {}", did_path))?;
+    let prog: IDLProg = did_str
+        .parse()
+        .with_context(|| format!("Failed to parse {}", did_path))?;
+    let mut env = TypeEnv::new();
+    let actor = check_prog(&mut env, &prog)
+        .with_context(|| format!("Failed to check {}", did_path))?
+        .with_context(|| format!("service is not defined in {}", did_path))?;
+    let methods = env.as_service(&actor)?;
+    let func = match methods.iter().find(|(n, _)| n == name) {
+        Some((_, func)) => env.as_func(func)?,
+        None => {
+            let names = methods.iter().map(|(n, _)| n.as_str());
+            bail!(
+                "method {} is not found in {}. available methods: {}",
+                name,
+                did_path,
+                names.collect::<Vec<_>>().join(", ")
+            )
+        }
+    };
+    let inline_all = |types: &[Type]| {
+        types
+            .iter()
+            .map(|ty| inline_type(&env, ty, &mut vec![]).map(|ty| ty.to_string()))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map(|types| types.join(", "))
+    };
+    let identifier = format!(
+        "{} : ({}) -> ({})",
+        name,
+        inline_all(&func.args)?,
+        inline_all(&func.rets)?
+    );
+    Ok(identifier.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+fn inline_type(env: &TypeEnv, ty: &Type, visiting: &mut Vec<String>) -> anyhow::Result<Type> {
+    let inline_fields = |fields: &[Field], visiting: &mut Vec<String>| {
+        fields
+            .iter()
+            .map(|f| {
+                Ok(Field {
+                    id: f.id.clone(),
+                    ty: inline_type(env, &f.ty, visiting)?,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+    };
+    let inlined = match ty.as_ref() {
+        TypeInner::Var(id) => {
+            ensure!(
+                !visiting.contains(id),
+                "recursive type {} can not be inlined. specify the identifier with its signature",
+                id
+            );
+            visiting.push(id.clone());
+            let inlined = inline_type(env, env.find_type(id)?, visiting)?;
+            visiting.pop();
+            return Ok(inlined);
+        }
+        TypeInner::Opt(inner) => TypeInner::Opt(inline_type(env, inner, visiting)?),
+        TypeInner::Vec(inner) => TypeInner::Vec(inline_type(env, inner, visiting)?),
+        TypeInner::Record(fields) => TypeInner::Record(inline_fields(fields, visiting)?),
+        TypeInner::Variant(fields) => TypeInner::Variant(inline_fields(fields, visiting)?),
+        _ => return Ok(ty.clone()),
+    };
+    Ok(inlined.into())
+}
+
+// determine if the string is a 20 bytes hex address, with or without 0x prefix
+pub fn is_valid_evm_address(address: &str) -> bool {
+    let address = address.strip_prefix("0x").unwrap_or(address);
+    address.len() == 40 && address.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// determine if the mixed-case address matches its EIP-55 checksum
+// NOTE: all lowercase or all uppercase addresses have no checksum and are accepted
+pub fn is_checksummed_evm_address(address: &str) -> bool {
+    if !is_valid_evm_address(address) {
+        return false;
+    }
+    let address = address.strip_prefix("0x").unwrap_or(address);
+    if address == address.to_lowercase() || address == address.to_uppercase() {
+        return true;
+    }
+    let mut hash = [0u8; 32];
+    let mut hasher = tiny_keccak::Keccak::v256();
+    tiny_keccak::Hasher::update(&mut hasher, address.to_lowercase().as_bytes());
+    tiny_keccak::Hasher::finalize(hasher, &mut hash);
+    address.chars().enumerate().all(|(i, c)| {
+        let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+        if c.is_ascii_digit() {
+            true
+        } else if nibble >= 8 {
+            c.is_ascii_uppercase()
+        } else {
+            c.is_ascii_lowercase()
+        }
+    })
+}
+
 // determine if the caller is a lens with arguments by CanisterMethodIdentifier
 // NOTE: only for snapshot_indexer_icp, relayer
 pub fn is_lens_with_args(identifier: CanisterMethodIdentifier) -> bool {
@@ -71,3 +627,163 @@ pub fn is_lens_with_args(identifier: CanisterMethodIdentifier) -> bool {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDENTIFIER: &str = "get_balance : (record { owner : principal; subaccount : opt blob }, variant { Asc; Desc; Limit : nat8 }, nat, vec int64) -> (text)";
+
+    fn encode(args: &str) -> anyhow::Result<Vec<u8>> {
+        let method_type = candid_method_type(IDENTIFIER, &None)?;
+        method_type.encode_args(&serde_yaml::from_str::<Vec<serde_yaml::Value>>(args)?)
+    }
+
+    fn encode_idl(args: &str) -> Vec<u8> {
+        let method_type = candid_method_type(IDENTIFIER, &None).unwrap();
+        candid_parser::parse_idl_args(args)
+            .unwrap()
+            .to_bytes_with_types(&method_type.env, &method_type.func.args)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_encode_args() {
+        assert_eq!(
+            encode(
+                r#"
+- owner: ryjl3-tyaaa-aaaaa-aaaba-cai
+- Desc
+- "340282366920938463463374607431768211456"
+- [1, -2]
+"#
+            )
+            .unwrap(),
+            encode_idl(
+                r#"(record { owner = principal "ryjl3-tyaaa-aaaaa-aaaba-cai"; subaccount = null }, variant { Desc }, 340282366920938463463374607431768211456 : nat, vec { 1 : int64; -2 : int64 })"#
+            )
+        );
+        assert_eq!(
+            encode(
+                r#"
+- owner: ryjl3-tyaaa-aaaaa-aaaba-cai
+  subaccount: "0x0102"
+- Limit: 10
+- 1
+- []
+"#
+            )
+            .unwrap(),
+            encode_idl(
+                r#"(record { owner = principal "ryjl3-tyaaa-aaaaa-aaaba-cai"; subaccount = opt blob "\01\02" }, variant { Limit = 10 : nat8 }, 1 : nat, vec {})"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_encode_args_with_invalid_args() {
+        let cases = [
+            ("[]", "takes 4 args, but 0 given"),
+            (
+                "[{owner: xxx}, Desc, 1, []]",
+                "args[0].owner: invalid principal",
+            ),
+            (
+                "[{owner: ryjl3-tyaaa-aaaaa-aaaba-cai, account: 1}, Desc, 1, []]",
+                "args[0]: unknown field account",
+            ),
+            ("[{}, Desc, 1, []]", "args[0].owner: missing field"),
+            (
+                "[{owner: ryjl3-tyaaa-aaaaa-aaaba-cai}, Up, 1, []]",
+                "args[1]: unknown tag Up",
+            ),
+            (
+                "[{owner: ryjl3-tyaaa-aaaaa-aaaba-cai}, {Limit: 256}, 1, []]",
+                "args[1].Limit: 256 is out of the range of nat8",
+            ),
+            (
+                "[{owner: ryjl3-tyaaa-aaaaa-aaaba-cai}, Desc, -1, []]",
+                "args[2]",
+            ),
+            (
+                "[{owner: ryjl3-tyaaa-aaaaa-aaaba-cai}, Desc, 1, [1, a]]",
+                "args[3][1]",
+            ),
+        ];
+        for (args, msg) in cases {
+            let err = encode(args).unwrap_err().to_string();
+            assert!(err.contains(msg), "{}: {}", args, err);
+        }
+    }
+
+    #[test]
+    fn test_encode_lens_calculate_args() {
+        let identifier = "get_result : (record { targets : vec text; args : record { symbol : text; limit : opt nat64 } }) -> (text)";
+        let method = |args: &str| DatasourceMethod {
+            identifier: identifier.to_string(),
+            interface: None,
+            args: serde_yaml::from_str(args).unwrap(),
+        };
+        assert!(encode_lens_calculate_args("lens", &method("[]"))
+            .unwrap()
+            .is_none());
+
+        let (env, ty) = parse_candid_type("record { symbol : text; limit : opt nat64 }").unwrap();
+        let expected =
+            candid_parser::parse_idl_args(r#"(record { symbol = "ETH"; limit = null })"#)
+                .unwrap()
+                .to_bytes_with_types(&env, &[ty])
+                .unwrap();
+        assert_eq!(
+            encode_lens_calculate_args("lens", &method("[{symbol: ETH}]")).unwrap(),
+            Some(expected)
+        );
+        assert!(encode_lens_calculate_args("lens", &method("[{symbol: 1}]")).is_err());
+        assert!(encode_lens_calculate_args("lens", &method("[{symbol: ETH}, {}]")).is_err());
+    }
+
+    #[test]
+    fn test_method_identifier_from_did() {
+        let did_path = std::env::temp_dir().join("csx_test_method_identifier_from_did.did");
+        std::fs::write(
+            &did_path,
+            r#"type Snapshot = record { value : SnapshotValue; timestamp : nat64 };
+type SnapshotValue = record { price : opt nat; symbol : text };
+type Tree = variant { leaf : nat; node : vec Tree };
+service : {
+  get_last_snapshot : () -> (Snapshot) query;
+  get_snapshot : (nat64) -> (Snapshot) query;
+  get_tree : () -> (Tree) query;
+}"#,
+        )
+        .unwrap();
+        let did_path = did_path.to_str().unwrap();
+
+        let identifier = method_identifier_from_did("get_snapshot", did_path).unwrap();
+        assert!(identifier.starts_with("get_snapshot : (nat64) -> (record {"));
+        assert!(identifier.contains("price : opt nat"));
+        assert!(!identifier.contains("Snapshot"));
+        // inlined identifier can be checked without the .did
+        assert!(candid_method_type(&identifier, &None).is_ok());
+        let err = method_identifier_from_did("get_snapshots", did_path).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("available methods: get_last_snapshot, get_snapshot, get_tree"));
+        let err = method_identifier_from_did("get_tree", did_path).unwrap_err();
+        assert!(err.to_string().contains("recursive type Tree"));
+    }
+
+    #[test]
+    fn test_is_checksummed_evm_address() {
+        assert!(is_checksummed_evm_address(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        ));
+        assert!(is_checksummed_evm_address(
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        ));
+        assert!(!is_checksummed_evm_address(
+            "0x5aaeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        ));
+        assert!(!is_checksummed_evm_address("0x5aAeb6053F3E94C9b9A09f"));
+    }
+}
@@ -1,6 +1,6 @@
 pub mod canisters;
+pub mod chains;
 pub mod components;
-pub mod oracle;
 pub mod project;
 pub mod scripts;
 pub mod templates;
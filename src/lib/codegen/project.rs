@@ -85,7 +85,7 @@ impl ProjectManifestComponentField {
             .unwrap()
             .to_str()
             .unwrap();
-        let generator = generator(component_type, &component_path, id)?;
+        let generator = generator(component_type, project_path, &component_path, id)?;
         Ok(generator)
     }
 }
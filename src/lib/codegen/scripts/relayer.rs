@@ -1,10 +1,10 @@
-use anyhow::Context;
+use anyhow::{ensure, Context};
 use candid::{Encode, Principal};
 use chainsight_cdk::web3::Web3CtxParam;
 
 use crate::{
     lib::{
-        codegen::components::relayer::RelayerComponentManifest,
+        codegen::components::relayer::{DestinationField, RelayerComponentManifest},
         utils::component_ids_manager::ComponentIdsManager,
     },
     types::Network,
@@ -27,11 +27,24 @@ pub fn generate_component_setup_args(
         }
     };
     let target_canister = resolver_name_or_id(&target_name_or_id);
-    let web3_ctx_param = Web3CtxParam {
-        url: manifest.destination.rpc_url.clone(),
+    // NOTE: the env of the canister decides the threshold ECDSA key to sign with
+    let ecdsa_key_name = manifest.destination.ecdsa_key_name();
+    if let Some(key_name) = ecdsa_key_name {
+        ensure!(
+            key_name.is_available_on(network),
+            "ecdsa_key_name {} is not available on the network {:?}",
+            serde_json::to_string(&key_name)?,
+            network
+        );
+    }
+    let web3_ctx_param = |destination: &DestinationField| Web3CtxParam {
+        url: destination.rpc_url.clone(),
         from: None,
-        chain_id: manifest.destination.network_id as u64,
-        env: network.to_sdk_env(),
+        chain_id: destination.network_id as u64,
+        env: match ecdsa_key_name {
+            Some(key_name) => key_name.to_sdk_env(),
+            None => network.to_sdk_env(),
+        },
     };
     let lens_target_name_or_id = manifest
         .lens_targets
@@ -44,20 +57,23 @@ pub fn generate_component_setup_args(
         })
         .unwrap_or_default();
 
-    let args = if lens_target_name_or_id.is_empty() {
+    let lens_targets = lens_target_name_or_id
+        .iter()
+        .map(|t| resolver_name_or_id(t))
+        .collect::<Vec<String>>();
+
+    // NOTE: omitted oracle address and rpc url are resolved from the chain registry
+    let destination = &manifest.resolved_destination();
+    let args = if lens_targets.is_empty() {
         Encode!(
-            &manifest.destination.oracle_address,
-            &web3_ctx_param,
+            &destination.oracle_address,
+            &web3_ctx_param(destination),
             &target_canister
         )
     } else {
-        let lens_targets = lens_target_name_or_id
-            .iter()
-            .map(|t| resolver_name_or_id(t))
-            .collect::<Vec<String>>();
         Encode!(
-            &manifest.destination.oracle_address,
-            &web3_ctx_param,
+            &destination.oracle_address,
+            &web3_ctx_param(destination),
             &target_canister,
             &lens_targets
         )
@@ -3,6 +3,7 @@ pub mod codegen;
 pub mod environment;
 pub mod ic_api;
 pub mod logger;
+pub mod network;
 pub mod utils;
 
 #[cfg(test)]
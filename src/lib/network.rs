@@ -0,0 +1,275 @@
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs,
+    io::Read,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use anyhow::Context;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Overrides the directory to cache responses in, `~/.cache/csx` by default
+pub const CACHE_DIR_ENV: &str = "CSX_CACHE_DIR";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+static CACHE_RESPONSES: AtomicBool = AtomicBool::new(false);
+
+/// Switch every network access of csx to offline, set by `--offline`
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Store responses of online requests in the cache for offline mode, set by `--cache-responses`
+pub fn set_cache_responses(cache_responses: bool) {
+    CACHE_RESPONSES.store(cache_responses, Ordering::Relaxed);
+}
+
+fn is_caching_responses() -> bool {
+    CACHE_RESPONSES.load(Ordering::Relaxed)
+}
+
+/// NetworkAccess for the current mode: online, or offline served from the cache.
+/// Online responses are written to the cache only if caching is enabled
+pub fn network_access() -> Box<dyn NetworkAccess> {
+    let cache = || ResponseCache::default_dir().map(ResponseCache::new);
+    if is_offline() {
+        Box::new(OfflineNetwork::new(cache()))
+    } else if is_caching_responses() {
+        Box::new(OnlineNetwork::new(cache()))
+    } else {
+        Box::new(OnlineNetwork::new(None))
+    }
+}
+
+/// HTTP request sent by csx
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn get(url: &str) -> Self {
+        Self {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            headers: BTreeMap::new(),
+            body: None,
+        }
+    }
+
+    pub fn post_json(url: &str, body: &serde_json::Value) -> Self {
+        Self {
+            method: "POST".to_string(),
+            url: url.to_string(),
+            headers: BTreeMap::from([("Content-Type".to_string(), "application/json".to_string())]),
+            body: Some(body.to_string()),
+        }
+    }
+
+    // identifies the request in the cache
+    fn cache_key(&self) -> String {
+        let mut hasher = tiny_keccak::Keccak::v256();
+        let mut feed = |s: &str| {
+            tiny_keccak::Hasher::update(&mut hasher, s.as_bytes());
+            tiny_keccak::Hasher::update(&mut hasher, b"\n");
+        };
+        feed(&self.method);
+        feed(&self.url);
+        for (key, value) in &self.headers {
+            feed(&format!("{}: {}", key.to_lowercase(), value));
+        }
+        feed(self.body.as_deref().unwrap_or_default());
+        let mut hash = [0u8; 32];
+        tiny_keccak::Hasher::finalize(hasher, &mut hash);
+        hex::encode(hash)
+    }
+}
+
+/// Every access of csx to the network goes through this, so that it can run offline
+pub trait NetworkAccess {
+    fn is_offline(&self) -> bool;
+
+    /// Send the request and get the body of the response
+    fn send(&self, request: &HttpRequest) -> anyhow::Result<Vec<u8>>;
+}
+
+/// Error of a request which needs the network in offline mode, without a cached response
+#[derive(Debug)]
+pub struct OfflineError {
+    pub url: String,
+}
+
+impl fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is not available offline: no cached response from a previous run with --cache-responses",
+            self.url
+        )
+    }
+}
+
+impl std::error::Error for OfflineError {}
+
+/// Whether the error is caused by a request skipped in offline mode
+pub fn is_offline_error(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| cause.is::<OfflineError>())
+}
+
+/// Responses of successful requests, stored on disk to be served in offline mode.
+/// Each response is a file named by the hash of the request in `$CSX_CACHE_DIR`, `~/.cache/csx` by default
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn default_dir() -> Option<PathBuf> {
+        match env::var_os(CACHE_DIR_ENV) {
+            Some(dir) => Some(PathBuf::from(dir)),
+            None => env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache/csx")),
+        }
+    }
+
+    pub fn get(&self, request: &HttpRequest) -> Option<Vec<u8>> {
+        fs::read(self.dir.join(request.cache_key())).ok()
+    }
+
+    pub fn put(&self, request: &HttpRequest, response: &[u8]) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(request.cache_key()), response)?;
+        Ok(())
+    }
+}
+
+pub struct OnlineNetwork {
+    agent: ureq::Agent,
+    cache: Option<ResponseCache>,
+}
+
+impl OnlineNetwork {
+    pub fn new(cache: Option<ResponseCache>) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(DEFAULT_TIMEOUT).build(),
+            cache,
+        }
+    }
+}
+
+impl NetworkAccess for OnlineNetwork {
+    fn is_offline(&self) -> bool {
+        false
+    }
+
+    fn send(&self, request: &HttpRequest) -> anyhow::Result<Vec<u8>> {
+        let mut req = self.agent.request(&request.method, &request.url);
+        for (key, value) in &request.headers {
+            req = req.set(key, value);
+        }
+        let res = match &request.body {
+            Some(body) => req.send_string(body)?,
+            None => req.call()?,
+        };
+        let mut body = vec![];
+        res.into_reader().read_to_end(&mut body)?;
+        if let Some(cache) = &self.cache {
+            // NOTE: the cache is only for offline mode, failing to write it does not fail the request
+            let _ = cache.put(request, &body);
+        }
+        Ok(body)
+    }
+}
+
+pub struct OfflineNetwork {
+    cache: Option<ResponseCache>,
+}
+
+impl OfflineNetwork {
+    pub fn new(cache: Option<ResponseCache>) -> Self {
+        Self { cache }
+    }
+}
+
+impl NetworkAccess for OfflineNetwork {
+    fn is_offline(&self) -> bool {
+        true
+    }
+
+    fn send(&self, request: &HttpRequest) -> anyhow::Result<Vec<u8>> {
+        self.cache
+            .as_ref()
+            .and_then(|cache| cache.get(request))
+            .ok_or_else(|| {
+                OfflineError {
+                    url: request.url.clone(),
+                }
+                .into()
+            })
+    }
+}
+
+/// Send the request and parse the response as JSON
+pub fn fetch_json(
+    network: &dyn NetworkAccess,
+    request: &HttpRequest,
+) -> anyhow::Result<serde_json::Value> {
+    let body = network.send(request)?;
+    serde_json::from_slice(&body)
+        .with_context(|| format!("Failed to parse the response from {} as JSON", request.url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_dir(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("csx_test_{}_{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let request = HttpRequest::get("https://example.com");
+        assert_eq!(request.cache_key(), request.clone().cache_key());
+        assert_ne!(
+            request.cache_key(),
+            HttpRequest::get("https://example.com/other").cache_key()
+        );
+        assert_ne!(
+            HttpRequest::post_json("https://example.com", &serde_json::json!({"id": 1}))
+                .cache_key(),
+            HttpRequest::post_json("https://example.com", &serde_json::json!({"id": 2}))
+                .cache_key()
+        );
+    }
+
+    #[test]
+    fn test_offline_network() {
+        let dir = cache_dir("offline_network");
+        let cache = ResponseCache::new(dir.clone());
+        let cached = HttpRequest::get("https://example.com/cached");
+        cache.put(&cached, br#"{"value":1}"#).unwrap();
+
+        let network = OfflineNetwork::new(Some(ResponseCache::new(dir.clone())));
+        let res = fetch_json(&network, &cached);
+        let missing = network.send(&HttpRequest::get("https://example.com/missing"));
+        fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(res.unwrap(), serde_json::json!({"value": 1}));
+        assert!(is_offline_error(&missing.unwrap_err()));
+        let without_cache = OfflineNetwork::new(None).send(&cached);
+        assert!(is_offline_error(&without_cache.unwrap_err()));
+        assert!(!is_offline_error(&anyhow::anyhow!("other error")));
+    }
+}
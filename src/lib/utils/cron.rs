@@ -0,0 +1,309 @@
+use std::{
+    collections::BTreeSet,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+
+const MINUTE: u32 = 60;
+const HOUR: u32 = 60 * MINUTE;
+const DAY: u32 = 24 * HOUR;
+const WEEK: u32 = 7 * DAY;
+// 1970-01-01, the origin of the rounded start timing, is a Thursday
+const EPOCH_DAY_OF_WEEK: u32 = 4;
+/// Number of fire times shown in previews of schedules
+pub const PREVIEW_COUNT: usize = 5;
+
+/// Schedule given by a cron expression in UTC: `minute hour day-of-month month day-of-week`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CronSchedule {
+    minutes: BTreeSet<u32>,
+    hours: BTreeSet<u32>,
+    /// None for `*`
+    days_of_month: Option<BTreeSet<u32>>,
+    months: Option<BTreeSet<u32>>,
+    days_of_week: Option<BTreeSet<u32>>,
+}
+
+/// Periodic timer running every `interval_sec`, `delay_sec` after the rounded start timing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoundedTimer {
+    pub interval_sec: u32,
+    pub delay_sec: u32,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression, or one of `@hourly`, `@daily`, `@midnight` and `@weekly`
+    pub fn parse(expr: &str) -> Result<Self> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" | "@yearly" | "@annually" => {
+                bail!("{} is not supported: months have different lengths", expr)
+            }
+            expr => expr,
+        };
+        let fields = expr.split_whitespace().collect::<Vec<_>>();
+        ensure!(
+            fields.len() == 5,
+            "cron expression must have 5 fields (minute hour day-of-month month day-of-week), but got {}",
+            fields.len()
+        );
+        let days_of_week = parse_field(fields[4], 0, 7)
+            .context("invalid day-of-week")?
+            .map(|days| days.into_iter().map(|d| d % 7).collect());
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59)
+                .context("invalid minute")?
+                .unwrap_or_else(|| (0..60).collect()),
+            hours: parse_field(fields[1], 0, 23)
+                .context("invalid hour")?
+                .unwrap_or_else(|| (0..24).collect()),
+            days_of_month: parse_field(fields[2], 1, 31).context("invalid day-of-month")?,
+            months: parse_field(fields[3], 1, 12).context("invalid month")?,
+            days_of_week,
+        })
+    }
+
+    /// Translate into a timer with the rounded start timing, if the schedule fires at even intervals
+    pub fn to_rounded_timer(&self) -> Result<RoundedTimer> {
+        ensure!(
+            self.months.is_none(),
+            "month must be *: months have different lengths, so they can't be represented by a fixed interval"
+        );
+        ensure!(
+            self.days_of_month.is_none(),
+            "day-of-month must be *: months have different lengths, so they can't be represented by a fixed interval"
+        );
+        let times_of_day = self
+            .hours
+            .iter()
+            .flat_map(|h| self.minutes.iter().map(move |m| h * HOUR + m * MINUTE))
+            .collect::<Vec<_>>();
+
+        if let Some(days_of_week) = &self.days_of_week {
+            ensure!(
+                days_of_week.len() == 1 && times_of_day.len() == 1,
+                "only a single time on a single day-of-week can be represented by a weekly interval"
+            );
+            let day = days_of_week.iter().next().unwrap();
+            let days_from_epoch = (day + 7 - EPOCH_DAY_OF_WEEK) % 7;
+            return Ok(RoundedTimer {
+                interval_sec: WEEK,
+                delay_sec: days_from_epoch * DAY + times_of_day[0],
+            });
+        }
+
+        let first = times_of_day[0];
+        let last = times_of_day[times_of_day.len() - 1];
+        if times_of_day.len() == 1 {
+            return Ok(RoundedTimer {
+                interval_sec: DAY,
+                delay_sec: first,
+            });
+        }
+        let interval = times_of_day[1] - first;
+        let evenly_spaced = times_of_day.windows(2).all(|w| w[1] - w[0] == interval)
+            && first + DAY - last == interval;
+        ensure!(
+            evenly_spaced,
+            "fire times are not evenly spaced through the day, so they can't be represented by a fixed interval"
+        );
+        Ok(RoundedTimer {
+            interval_sec: interval,
+            delay_sec: first,
+        })
+    }
+}
+
+// None for `*`, or the values in [min, max]
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Option<BTreeSet<u32>>> {
+    if field == "*" {
+        return Ok(None);
+    }
+    let mut values = BTreeSet::new();
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .with_context(|| format!("invalid step: {}", step))?;
+                ensure!(step > 0, "step must be positive");
+                (range, step)
+            }
+            None => (item, 1),
+        };
+        let (from, to) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((from, to)) => (parse_value(from)?, parse_value(to)?),
+                None => {
+                    let value = parse_value(range)?;
+                    // `a/n` runs from a to the max
+                    (value, if item.contains('/') { max } else { value })
+                }
+            },
+        };
+        ensure!(
+            min <= from && from <= to && to <= max,
+            "{} is out of range {}-{}",
+            range,
+            min,
+            max
+        );
+        values.extend((from..=to).step_by(step as usize));
+    }
+    Ok(Some(values))
+}
+
+fn parse_value(value: &str) -> Result<u32> {
+    value
+        .parse()
+        .with_context(|| format!("{} is not a number", value))
+}
+
+/// Next `n` times (unix time in seconds) the timer set by `set_task` fires after `now`
+pub fn next_fire_times(
+    now: u64,
+    interval_sec: u32,
+    delay_sec: u32,
+    is_round_start_timing: bool,
+    n: usize,
+) -> Vec<u64> {
+    let interval = interval_sec as u64;
+    let first = if is_round_start_timing && interval > 0 {
+        now / interval * interval + interval + delay_sec as u64
+    } else {
+        now + delay_sec as u64
+    };
+    (0..n as u64).map(|i| first + i * interval).collect()
+}
+
+/// Format unix time in seconds as `YYYY-MM-DD hh:mm:ss UTC`
+pub fn format_utc(timestamp: u64) -> String {
+    let days = (timestamp / DAY as u64) as i64;
+    let secs = (timestamp % DAY as u64) as u32;
+    // ref: http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        secs / HOUR,
+        secs % HOUR / MINUTE,
+        secs % MINUTE
+    )
+}
+
+/// Current unix time in seconds
+pub fn now_sec() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timer(expr: &str) -> Result<RoundedTimer> {
+        CronSchedule::parse(expr)?.to_rounded_timer()
+    }
+
+    #[test]
+    fn test_to_rounded_timer() {
+        let rounded = |interval_sec, delay_sec| RoundedTimer {
+            interval_sec,
+            delay_sec,
+        };
+        assert_eq!(timer("5 * * * *").unwrap(), rounded(HOUR, 5 * MINUTE));
+        assert_eq!(timer("@hourly").unwrap(), rounded(HOUR, 0));
+        assert_eq!(timer("0 0 * * *").unwrap(), rounded(DAY, 0));
+        assert_eq!(
+            timer("30 9 * * *").unwrap(),
+            rounded(DAY, 9 * HOUR + 30 * MINUTE)
+        );
+        assert_eq!(timer("*/15 * * * *").unwrap(), rounded(15 * MINUTE, 0));
+        assert_eq!(
+            timer("10/20 * * * *").unwrap(),
+            rounded(20 * MINUTE, 10 * MINUTE)
+        );
+        assert_eq!(timer("0 */6 * * *").unwrap(), rounded(6 * HOUR, 0));
+        assert_eq!(timer("0 1,13 * * *").unwrap(), rounded(12 * HOUR, HOUR));
+        assert_eq!(timer("* * * * *").unwrap(), rounded(MINUTE, 0));
+        // Thursday, the day of the epoch
+        assert_eq!(timer("0 0 * * 4").unwrap(), rounded(WEEK, 0));
+        // Sunday, 3 days after Thursday
+        assert_eq!(timer("@weekly").unwrap(), rounded(WEEK, 3 * DAY));
+        assert_eq!(
+            timer("0 12 * * 7").unwrap(),
+            rounded(WEEK, 3 * DAY + 12 * HOUR)
+        );
+
+        // not evenly spaced
+        assert!(timer("0 */5 * * *").is_err());
+        assert!(timer("0 1,2,4 * * *").is_err());
+        assert!(timer("*/7 * * * *").is_err());
+        // calendar months
+        assert!(timer("0 0 1 * *").is_err());
+        assert!(timer("0 0 * 1 *").is_err());
+        assert!(timer("@monthly").is_err());
+        // weekdays
+        assert!(timer("0 0 * * 1-5").is_err());
+        assert!(timer("0 0,12 * * 1").is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(CronSchedule::parse("0 * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("0 24 * * *").is_err());
+        assert!(CronSchedule::parse("0 0 0 * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("5-1 * * * *").is_err());
+        assert!(CronSchedule::parse("a * * * *").is_err());
+        assert_eq!(
+            CronSchedule::parse("0 0 * * 7").unwrap(),
+            CronSchedule::parse("0 0 * * 0").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_next_fire_times() {
+        // 2024-01-01 10:02:00 UTC
+        let now = 1704103320;
+        assert_eq!(
+            next_fire_times(now, HOUR, 5 * MINUTE, true, 3)
+                .into_iter()
+                .map(format_utc)
+                .collect::<Vec<_>>(),
+            vec![
+                "2024-01-01 11:05:00 UTC",
+                "2024-01-01 12:05:00 UTC",
+                "2024-01-01 13:05:00 UTC"
+            ]
+        );
+        assert_eq!(
+            next_fire_times(now, DAY, 60, false, 2),
+            vec![now + 60, now + 60 + DAY as u64]
+        );
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_utc(951782400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(format_utc(1704103320), "2024-01-01 10:02:00 UTC");
+    }
+}
@@ -2,11 +2,13 @@ use std::{panic, path::Path};
 
 pub mod clap;
 pub mod component_ids_manager;
+pub mod cron;
 pub mod dfx;
 pub mod env;
 pub mod identity;
 pub mod interaction;
 pub mod paths;
+pub mod rpc;
 pub mod serializer;
 pub mod url;
 
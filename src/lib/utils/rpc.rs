@@ -0,0 +1,359 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::lib::network::{fetch_json, network_access, HttpRequest, NetworkAccess};
+
+/// EVM JSON-RPC endpoint used by a component, checked before code generation
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcEndpoint {
+    /// Field of the manifest the endpoint comes from, for messages
+    pub field: String,
+    pub url: String,
+    pub chain_id: u64,
+    /// Addresses of contracts expected to be deployed on the chain
+    pub contracts: Vec<String>,
+}
+
+impl RpcEndpoint {
+    pub fn new(field: &str, url: &str, chain_id: u64, contracts: Vec<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            url: url.to_string(),
+            chain_id,
+            contracts,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RpcEndpointReport {
+    pub chain_id: u64,
+    /// Round trip time of eth_chainId
+    pub latency: Duration,
+}
+
+/// Error of an endpoint which responds, but not for the chain or the contracts in the manifest
+#[derive(Debug)]
+pub enum RpcEndpointMismatch {
+    ChainId {
+        url: String,
+        actual: u64,
+        expected: u64,
+    },
+    NoContract {
+        address: String,
+        chain_id: u64,
+    },
+}
+
+impl fmt::Display for RpcEndpointMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcEndpointMismatch::ChainId {
+                url,
+                actual,
+                expected,
+            } => write!(
+                f,
+                "{} serves chain {}, but chain {} is specified",
+                url, actual, expected
+            ),
+            RpcEndpointMismatch::NoContract { address, chain_id } => write!(
+                f,
+                "no contract is deployed at {} on chain {}",
+                address, chain_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RpcEndpointMismatch {}
+
+/// Whether the error is caused by the endpoint not matching the manifest,
+/// rather than by failing to reach it
+pub fn is_rpc_endpoint_mismatch(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| cause.is::<RpcEndpointMismatch>())
+}
+
+/// Client sending JSON-RPC requests to EVM chains
+pub trait RpcClient {
+    /// Call `method` and get the `result` of the response
+    fn request(&self, url: &str, method: &str, params: Vec<Value>) -> Result<Value>;
+}
+
+#[derive(Serialize)]
+struct JsonRpcRequest {
+    method: String,
+    params: Vec<Value>,
+    id: u64,
+    jsonrpc: String,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize, Debug)]
+struct JsonRpcResponse {
+    id: u64,
+    jsonrpc: String,
+    result: Option<Value>,
+    error: Option<Value>,
+}
+
+/// RpcClient over HTTP(S), through the NetworkAccess
+pub struct HttpRpcClient {
+    network: Box<dyn NetworkAccess>,
+}
+
+impl HttpRpcClient {
+    pub fn new(network: Box<dyn NetworkAccess>) -> Self {
+        Self { network }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.network.is_offline()
+    }
+}
+
+impl Default for HttpRpcClient {
+    fn default() -> Self {
+        Self::new(network_access())
+    }
+}
+
+impl RpcClient for HttpRpcClient {
+    fn request(&self, url: &str, method: &str, params: Vec<Value>) -> Result<Value> {
+        let request = JsonRpcRequest {
+            method: method.to_string(),
+            params,
+            id: 1,
+            jsonrpc: "2.0".to_string(),
+        };
+        let res = fetch_json(
+            self.network.as_ref(),
+            &HttpRequest::post_json(url, &serde_json::to_value(&request)?),
+        )?;
+        let res = serde_json::from_value::<JsonRpcResponse>(res)?;
+        if let Some(err) = res.error {
+            anyhow::bail!("Error in response {} from calling {}", err, method);
+        }
+        res.result
+            .with_context(|| format!("No result in response from calling {}", method))
+    }
+}
+
+/// Check the endpoint serves the chain of `chain_id` and the contracts are deployed there
+pub fn check_rpc_endpoint(
+    client: &dyn RpcClient,
+    endpoint: &RpcEndpoint,
+) -> Result<RpcEndpointReport> {
+    let started = Instant::now();
+    let result = client.request(&endpoint.url, "eth_chainId", vec![])?;
+    let latency = started.elapsed();
+    let chain_id = parse_quantity(&result).context("invalid result of eth_chainId")?;
+    if chain_id != endpoint.chain_id {
+        return Err(RpcEndpointMismatch::ChainId {
+            url: endpoint.url.clone(),
+            actual: chain_id,
+            expected: endpoint.chain_id,
+        }
+        .into());
+    }
+
+    for contract in &endpoint.contracts {
+        let address = if contract.starts_with("0x") {
+            contract.clone()
+        } else {
+            format!("0x{}", contract)
+        };
+        let code = client.request(
+            &endpoint.url,
+            "eth_getCode",
+            vec![json!(address), json!("latest")],
+        )?;
+        let code = code.as_str().context("invalid result of eth_getCode")?;
+        if code
+            .trim_start_matches("0x")
+            .trim_start_matches('0')
+            .is_empty()
+        {
+            return Err(RpcEndpointMismatch::NoContract { address, chain_id }.into());
+        }
+    }
+
+    Ok(RpcEndpointReport { chain_id, latency })
+}
+
+fn parse_quantity(value: &Value) -> Result<u64> {
+    let hex = value.as_str().context("quantity must be a hex string")?;
+    let digits = hex
+        .strip_prefix("0x")
+        .with_context(|| format!("quantity must start with 0x: {}", hex))?;
+    Ok(u64::from_str_radix(digits, 16)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::*;
+    use crate::lib::network::OnlineNetwork;
+
+    struct MockRpcClient {
+        chain_id: &'static str,
+        codes: HashMap<String, &'static str>,
+    }
+
+    impl RpcClient for MockRpcClient {
+        fn request(&self, _url: &str, method: &str, params: Vec<Value>) -> Result<Value> {
+            match method {
+                "eth_chainId" => Ok(json!(self.chain_id)),
+                "eth_getCode" => {
+                    let address = params[0].as_str().unwrap().to_lowercase();
+                    Ok(json!(self.codes.get(&address).copied().unwrap_or("0x")))
+                }
+                _ => anyhow::bail!("method not found: {}", method),
+            }
+        }
+    }
+
+    const ORACLE: &str = "0x0539a0ef8e5e60891fff0958a059e049e43020d9";
+
+    fn endpoint(chain_id: u64, contracts: &[&str]) -> RpcEndpoint {
+        RpcEndpoint::new(
+            "destination.rpc_url",
+            "http://localhost:8545",
+            chain_id,
+            contracts.iter().map(|c| c.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_check_rpc_endpoint() {
+        let client = MockRpcClient {
+            chain_id: "0x89",
+            codes: HashMap::from([(ORACLE.to_string(), "0x6080604052")]),
+        };
+        let report = check_rpc_endpoint(&client, &endpoint(137, &[ORACLE])).unwrap();
+        assert_eq!(report.chain_id, 137);
+        // without 0x prefix
+        assert!(check_rpc_endpoint(&client, &endpoint(137, &[&ORACLE[2..]])).is_ok());
+
+        // chain id mismatch
+        let err = check_rpc_endpoint(&client, &endpoint(1, &[ORACLE])).unwrap_err();
+        assert!(is_rpc_endpoint_mismatch(&err));
+        // no code at the address
+        let err = check_rpc_endpoint(
+            &client,
+            &endpoint(137, &["0xe7f1725e7734ce288f8367e1bb143e90bb3f0512"]),
+        )
+        .unwrap_err();
+        assert!(is_rpc_endpoint_mismatch(&err));
+
+        // invalid response
+        let client = MockRpcClient {
+            chain_id: "137",
+            codes: HashMap::new(),
+        };
+        let err = check_rpc_endpoint(&client, &endpoint(137, &[])).unwrap_err();
+        assert!(!is_rpc_endpoint_mismatch(&err));
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(parse_quantity(&json!("0x1")).unwrap(), 1);
+        assert_eq!(parse_quantity(&json!("0xaa36a7")).unwrap(), 11155111);
+        assert!(parse_quantity(&json!("1")).is_err());
+        assert!(parse_quantity(&json!(1)).is_err());
+    }
+
+    // JSON-RPC server answering eth_chainId with `chain_id`, and eth_getCode with empty code
+    fn serve_mock_json_rpc(chain_id: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    let lower = line.to_lowercase();
+                    if let Some(len) = lower.strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: Value = serde_json::from_slice(&body).unwrap();
+                let response = match request["method"].as_str().unwrap() {
+                    "eth_chainId" => json!({"jsonrpc": "2.0", "id": 1, "result": chain_id}),
+                    "eth_getCode" => json!({"jsonrpc": "2.0", "id": 1, "result": "0x"}),
+                    _ => json!({
+                        "jsonrpc": "2.0",
+                        "id": 1,
+                        "error": {"code": -32601, "message": "method not found"}
+                    }),
+                }
+                .to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    response.len(),
+                    response
+                )
+                .unwrap();
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_http_rpc_client() {
+        let url = serve_mock_json_rpc("0x1");
+        let client = HttpRpcClient::new(Box::new(OnlineNetwork::new(None)));
+        assert_eq!(
+            client.request(&url, "eth_chainId", vec![]).unwrap(),
+            json!("0x1")
+        );
+        assert!(client.request(&url, "eth_blockNumber", vec![]).is_err());
+
+        let report =
+            check_rpc_endpoint(&client, &RpcEndpoint::new("rpc_url", &url, 1, vec![])).unwrap();
+        assert_eq!(report.chain_id, 1);
+        let err = check_rpc_endpoint(&client, &RpcEndpoint::new("rpc_url", &url, 137, vec![]))
+            .unwrap_err();
+        assert!(is_rpc_endpoint_mismatch(&err));
+        let err = check_rpc_endpoint(
+            &client,
+            &RpcEndpoint::new("rpc_url", &url, 1, vec![ORACLE.to_string()]),
+        )
+        .unwrap_err();
+        assert!(is_rpc_endpoint_mismatch(&err));
+
+        // unreachable endpoint
+        let unreachable = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let err = check_rpc_endpoint(
+            &client,
+            &RpcEndpoint::new("rpc_url", &unreachable, 1, vec![]),
+        )
+        .unwrap_err();
+        assert!(!is_rpc_endpoint_mismatch(&err));
+    }
+}
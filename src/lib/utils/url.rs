@@ -1,26 +1,54 @@
+use std::net::IpAddr;
+
 use anyhow::{Ok, Result};
 use serde::{Deserialize, Serialize};
 
 // Reference source: https://github.com/horizonx-tech/chainsight-backend/blob/c16741812c63ddf7cbe7519d12743c5988daf689/functions/internal/src/app/deploy_relayer/deploy_relayer.rs#L195-L236
 
-pub fn is_supporting_ipv6_url(url_str: &str) -> Result<()> {
+/// How to treat URLs in manifests not reachable by HTTPS outcalls, which only connect over IPv6
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Ipv6Policy {
+    /// Fail the command
+    Error,
+    /// Log a warning and continue
+    #[default]
+    Warn,
+    /// Skip the check
+    Off,
+}
+
+/// Resolves domains to IP addresses
+pub trait DnsResolver {
+    fn lookup(&self, domain: &str) -> Result<Vec<IpAddr>>;
+}
+
+pub struct SystemDnsResolver;
+impl DnsResolver for SystemDnsResolver {
+    fn lookup(&self, domain: &str) -> Result<Vec<IpAddr>> {
+        Ok(dns_lookup::lookup_host(domain)?)
+    }
+}
+
+/// Check the URL can be called by HTTPS outcalls of the Internet Computer
+pub fn check_ipv6_reachability(resolver: &dyn DnsResolver, url_str: &str) -> Result<()> {
     let url = url_str.parse::<url::Url>()?;
     let scheme = url.scheme();
     if scheme != "https" {
-        anyhow::bail!("Only HTTPS is acceptable for RPC URL, but got: {}", scheme)
+        anyhow::bail!("Only HTTPS is acceptable for URL, but got: {}", scheme)
     }
     let host = url
         .host()
-        .ok_or_else(|| anyhow::anyhow!("No host in RPC URL"))?;
+        .ok_or_else(|| anyhow::anyhow!("No host in URL"))?;
     match host {
         url::Host::Ipv4(_) => anyhow::bail!("IPv4 address is not acceptable in Internet Computer"),
         url::Host::Ipv6(_) => Ok(()),
-        url::Host::Domain(domain) => is_ipv6_supported_domain(domain),
+        url::Host::Domain(domain) => is_ipv6_supported_domain(resolver, domain),
     }
 }
 
-fn is_ipv6_supported_domain(domain: &str) -> Result<()> {
-    let ips: Vec<std::net::IpAddr> = dns_lookup::lookup_host(domain)?;
+fn is_ipv6_supported_domain(resolver: &dyn DnsResolver, domain: &str) -> Result<()> {
+    let ips = resolver.lookup(domain)?;
     for ip in ips {
         if ip.is_ipv6() {
             return Ok(());
@@ -29,67 +57,54 @@ fn is_ipv6_supported_domain(domain: &str) -> Result<()> {
     anyhow::bail!("No IPv6 address found, IPv4 address is not acceptable in Internet Computer")
 }
 
-#[derive(Serialize, Deserialize)]
-struct JsonRpcRequest {
-    method: String,
-    params: Vec<serde_json::Value>,
-    id: u64,
-    jsonrpc: String,
-}
-
-#[allow(dead_code)]
-#[derive(Deserialize, Debug)]
-struct JsonRpcResponse {
-    id: u64,
-    jsonrpc: String,
-    result: Option<String>,
-    error: Option<String>,
-}
-
-pub fn is_valid_rpc_url(url: &str) -> Result<()> {
-    let request = JsonRpcRequest {
-        method: "eth_blockNumber".to_string(),
-        params: Vec::new(),
-        id: 1,
-        jsonrpc: "2.0".to_string(),
-    };
-
-    let res = ureq::post(url)
-        .set("Content-Type", "application/json")
-        .send_json(&request)?
-        .into_json::<JsonRpcResponse>()?;
-    if let Some(err) = res.error {
-        anyhow::bail!("Error in response {:?} from calling eth_blockNumber", err);
-    }
-
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
 
     // ref: test ip,domains
     //   https://developers.google.com/speed/public-dns/docs/using
 
     #[test]
-    fn test_is_supporting_ipv6_url() {
-        assert!(is_supporting_ipv6_url("https://ipv4.google.com").is_err());
-        assert!(is_supporting_ipv6_url("https://ipv6.google.com").is_ok());
-        assert!(is_supporting_ipv6_url("https://eth-mainnet.g.alchemy.com").is_ok());
-        assert!(is_supporting_ipv6_url("https://eth-mainnet.g.alchemy.com/v2/${KEY}").is_ok());
+    fn test_check_ipv6_reachability_with_system_resolver() {
+        let check = |url| check_ipv6_reachability(&SystemDnsResolver, url);
+        assert!(check("https://ipv4.google.com").is_err());
+        assert!(check("https://ipv6.google.com").is_ok());
+        assert!(check("https://eth-mainnet.g.alchemy.com").is_ok());
+        assert!(check("https://eth-mainnet.g.alchemy.com/v2/${KEY}").is_ok());
     }
 
     #[test]
     fn test_is_ipv6_supported_domain() {
-        assert!(is_ipv6_supported_domain("api.coingecko.com").is_ok());
-        assert!(is_ipv6_supported_domain("ipv6.google.com").is_ok());
-        assert!(is_ipv6_supported_domain("ipv4.google.com").is_err());
+        assert!(is_ipv6_supported_domain(&SystemDnsResolver, "api.coingecko.com").is_ok());
+        assert!(is_ipv6_supported_domain(&SystemDnsResolver, "ipv6.google.com").is_ok());
+        assert!(is_ipv6_supported_domain(&SystemDnsResolver, "ipv4.google.com").is_err());
+    }
+
+    struct MockDnsResolver(HashMap<&'static str, Vec<IpAddr>>);
+    impl DnsResolver for MockDnsResolver {
+        fn lookup(&self, domain: &str) -> Result<Vec<IpAddr>> {
+            self.0
+                .get(domain)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("failed to lookup address information"))
+        }
     }
 
     #[test]
-    fn test_is_valid_rpc_url() {
-        assert!(is_valid_rpc_url("https://eth.llamarpc.com").is_ok());
-        assert!(is_valid_rpc_url("https://eth-mainnet.g.alchemy.com/v2/${KEY}").is_err());
+    fn test_check_ipv6_reachability() {
+        let v4: IpAddr = "93.184.216.34".parse().unwrap();
+        let v6: IpAddr = "2606:2800:220:1:248:1893:25c8:1946".parse().unwrap();
+        let resolver = MockDnsResolver(HashMap::from([
+            ("dual.example.com", vec![v4, v6]),
+            ("v4only.example.com", vec![v4]),
+        ]));
+        assert!(check_ipv6_reachability(&resolver, "https://dual.example.com/v2/${KEY}").is_ok());
+        assert!(check_ipv6_reachability(&resolver, "https://[2606:2800:220:1::1]").is_ok());
+        assert!(check_ipv6_reachability(&resolver, "https://v4only.example.com").is_err());
+        assert!(check_ipv6_reachability(&resolver, "https://93.184.216.34").is_err());
+        assert!(check_ipv6_reachability(&resolver, "http://dual.example.com").is_err());
+        assert!(check_ipv6_reachability(&resolver, "https://unknown.example.com").is_err());
     }
 }
@@ -11,6 +11,7 @@ use config::cli_version_str;
 use lib::{
     environment::EnvironmentImpl,
     logger::create_root_logger,
+    network::{set_cache_responses, set_offline},
     utils::{
         self,
         dfx::{DfxWrapper, DfxWrapperNetwork},
@@ -31,6 +32,19 @@ struct Cli {
     #[arg(long, short, action = ArgAction::Count, global = true)]
     quiet: u8,
 
+    /// Runs without the network. Network-dependent steps use the responses cached by a previous run with --cache-responses, or are skipped with warnings.
+    #[arg(long, global = true, env = "CSX_OFFLINE")]
+    offline: bool,
+
+    /// Caches the responses of network requests for later runs with --offline. They are stored in $CSX_CACHE_DIR, ~/.cache/csx by default.
+    #[arg(
+        long,
+        global = true,
+        env = "CSX_CACHE_RESPONSES",
+        conflicts_with = "offline"
+    )]
+    cache_responses: bool,
+
     #[command(subcommand)]
     command: Command,
 }
@@ -46,6 +60,8 @@ fn run() -> i32 {
     let _ = cache_envfile(None); // NOTE: Proceed regardless of the absence of an env file or environment variables.
     let verbose_level = args.verbose as i64 - args.quiet as i64;
     let logger = create_root_logger(verbose_level);
+    set_offline(args.offline);
+    set_cache_responses(args.cache_responses);
     info_on_bin_deps_for_csx(&logger);
 
     let env = EnvironmentImpl::new().with_logger(logger.clone());